@@ -1,4 +1,4 @@
-use std::{thread::sleep, time::Duration};
+use std::{path::PathBuf, thread::sleep, time::Duration};
 
 use app_lib::*;
 use clap::Parser;
@@ -9,6 +9,23 @@ struct Args {
     //Download Java
     #[arg(long="download-java")]
     java_version: Option<JavaVersion>,
+
+    /// Vendor to download Java from, defaults to the one configured for that version
+    #[arg(long="java-vendor")]
+    java_vendor: Option<JavaVendor>,
+
+    /// Find Java runtimes already on this host (JAVA_HOME, /usr/lib/jvm, SDKMAN) and register them in the config
+    #[arg(long="discover-java")]
+    discover_java: bool,
+
+    /// Update the Paper/Folia instance at this path to the newest build for its Minecraft version,
+    /// or a Bedrock instance to the newest release
+    #[arg(long="update")]
+    update_path: Option<PathBuf>,
+
+    /// Build channel to update from
+    #[arg(long="channel", default_value="default")]
+    channel: PaperChannel,
 }
 
 fn main() -> Result<(), LibError> {
@@ -16,42 +33,27 @@ fn main() -> Result<(), LibError> {
     let config = config_read_config()?;
     let args = Args::parse();
 
+    if args.discover_java {
+        for installation in java_register_installations()? {
+            println!("Found Java {} ({}) from {} at {}", installation.major, installation.version, installation.vendor, installation.home);
+        }
+    }
     match args.java_version {
         Some(java_version) => {
-            let java_dir = config.directories.java_dir;
-            match java_version {
-                JavaVersion::Java8 => {
-                    println!("Downloading Java 8...");
-                    #[cfg(target_os = "linux")]
-                    download_java_openjdk_amazon_correto(LINUX_JAVA_8_URL, LINUX_JAVA_8_SHA256, true, java_dir, JavaVersion::Java8)?;
-                    #[cfg(target_os = "windows")]
-                    download_java_openjdk_amazon_correto(WINDOWS_JAVA_8_URL, WINDOWS_JAVA_8_SHA256, true, java_dir, JavaVersion::Java8)?;
-                }
-                JavaVersion::Java17 => {
-                    println!("Downloading Java 17...");
-                    #[cfg(target_os = "linux")]
-                    download_java_openjdk_amazon_correto(LINUX_JAVA_17_URL, LINUX_JAVA_17_SHA256, true, java_dir, JavaVersion::Java17)?;
-                    #[cfg(target_os = "windows")]
-                    download_java_openjdk_amazon_correto(WINDOWS_JAVA_17_URL, WINDOWS_JAVA_17_SHA256, true, java_dir, JavaVersion::Java17)?;
-                }
-                JavaVersion::Java21 => {
-                    println!("Downloading Java 21...");
-                    #[cfg(target_os = "linux")]
-                    download_java_openjdk_amazon_correto(LINUX_JAVA_21_URL, LINUX_JAVA_21_SHA256, true, java_dir, JavaVersion::Java21)?;
-                    #[cfg(target_os = "windows")]
-                    download_java_openjdk_amazon_correto(WINDOWS_JAVA_21_URL, WINDOWS_JAVA_21_SHA256, true, java_dir, JavaVersion::Java21)?;
-                }
-                JavaVersion::Java25 => {
-                    println!("Downloading Java 25...");
-                    #[cfg(target_os = "linux")]
-                    download_java_openjdk_amazon_correto(LINUX_JAVA_25_URL, LINUX_JAVA_25_SHA256, true, java_dir, JavaVersion::Java25)?;
-                    #[cfg(target_os = "windows")]
-                    download_java_openjdk_amazon_correto(WINDOWS_JAVA_25_URL, WINDOWS_JAVA_25_SHA256, true, java_dir, JavaVersion::Java25)?;
-                }
-            }
+            let vendor = args.java_vendor.unwrap_or_else(|| config.java.vendor_for(java_version));
+            println!("Downloading {java_version} from {vendor}...");
+            download_java_runtime(vendor, java_version, &TerminalProgress::new(), config.directories.java_dir)?;
         }
         None => sleep(Duration::from_nanos(0)),
     }
+    if let Some(path) = args.update_path {
+        let installer = installer_for(instance_read_kind(&path)?.server_kind())?;
+        let options = InstallOptions { channel: args.channel, ..InstallOptions::default() };
+        let updated = installer.update(&path, &options, &TerminalProgress::new())?;
+        if updated {
+            println!("Done!");
+        }
+    }
     println!("Hello, cli!");
     
     
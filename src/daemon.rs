@@ -3,8 +3,12 @@ use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 use tokio::process::ChildStdin;
 use std::collections::HashMap;
+use std::path::Path;
 use tokio::io::{AsyncBufReadExt, BufReader, AsyncWriteExt};
 use tokio::process::Command;
+use tokio::sync::mpsc;
+use serde::Deserialize;
+use app_lib::*;
 
 static PROGRAM_IO: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 static PROGRAM_STDIN: Lazy<Mutex<HashMap<String, ChildStdin>>> = Lazy::new(|| Mutex::new(HashMap::new()));
@@ -39,6 +43,15 @@ async fn handle_socket(mut socket: WebSocket) {
             match msg {
                 Message::Text(utf8_bytes) => {
                     println!("Text received: {}", utf8_bytes);
+                    if let Ok(request) = serde_json::from_str::<InstallRequest>(&utf8_bytes) {
+                        if let Err(error) = handle_install(&mut socket, request).await {
+                            println!("Error sending: {}", error);
+                            send_close_message(socket, 1011, &format!("Error occured: {}", error))
+                                .await;
+                            break;
+                        }
+                        continue;
+                    }
                     let result = socket
                         .send(Message::Text(
                             format!("Echo back text: {}", utf8_bytes).into(),
@@ -92,12 +105,73 @@ async fn handle_socket(mut socket: WebSocket) {
 async fn send_close_message(mut socket: WebSocket, code: u16, reason: &str) {
     _ = socket
         .send(Message::Close(Some(CloseFrame {
-            code: code,
+            code,
             reason: reason.into(),
         })))
         .await;
 }
 
+// {"action":"install","loader":"paper","version":"1.21.4","path":"/srv/minecraft"}
+// Proxies are installed the same way, e.g. "loader":"velocity" with "version":"latest"
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum InstallRequest {
+    Install {
+        loader: ServerKind,
+        version: String,
+        path: String,
+        #[serde(default)]
+        build: Option<String>,
+    },
+}
+
+// Forwards library progress events to the websocket task.
+struct ChannelProgress {
+    sender: mpsc::UnboundedSender<ProgressEvent>,
+}
+
+impl ProgressReporter for ChannelProgress {
+    fn report(&self, event: ProgressEvent) {
+        _ = self.sender.send(event);
+    }
+}
+
+async fn handle_install(socket: &mut WebSocket, request: InstallRequest) -> Result<(), axum::Error> {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let task = tokio::task::spawn_blocking(move || {
+        let reporter = ChannelProgress { sender };
+        let result = install_server(request, &reporter);
+        progress_report_result(&reporter, &result);
+    });
+
+    // The channel closes once the install task drops its reporter.
+    while let Some(event) = receiver.recv().await {
+        let text = match serde_json::to_string(&event) {
+            Ok(text) => text,
+            Err(e) => format!("{{\"event\":\"failed\",\"message\":\"{e}\"}}"),
+        };
+        socket.send(Message::Text(text.into())).await?;
+    }
+    _ = task.await;
+    Ok(())
+}
+
+fn install_server(request: InstallRequest, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let InstallRequest::Install { loader, version, path, build } = request;
+    let build = match build {
+        Some(build) => build.parse()?,
+        None => BuildSelection::default(),
+    };
+    config_create_config()?;
+    let installer = installer_for(loader)?;
+    let ver = installer.resolve_version(&version)?;
+    if let Some(java_ver) = installer.java_version(&ver)? {
+        java_ensure_installed(java_ver, reporter)?;
+    }
+    let options = InstallOptions { build, ..InstallOptions::default() };
+    installer.install(&ver, Path::new(&path), &options, reporter)
+}
+
 async fn run_program_background(name: &str, cmd: &str, args: &[&str]) {
     let name = name.to_string();
     let cmd = cmd.to_string();
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use app_lib::*;
 use clap::Parser;
@@ -6,140 +6,133 @@ use clap::Parser;
 #[derive(Parser, Debug)]
 #[command(about, version, long_about = None)]
 struct Args {
-    /// Minecraft Version to download
+    /// Minecraft Version to download (or latest.release / latest.snapshot), the proxy version (or latest) with --proxy,
+    /// the Bedrock version (or latest / preview) with --bedrock
     #[arg(short='v', long="mc-version")]
     mcversion: String,
 
+    /// Which kinds of Minecraft versions to accept (defaults to all)
+    #[arg(short='k', long="version-kind", value_delimiter=',')]
+    version_kinds: Vec<VersionKind>,
+
     /// Which Modloader to use
-    #[arg(short='m', long="modloader")]
-    modloader: Modloaders,
+    #[arg(short='m', long="modloader", required_unless_present_any=["proxy", "bedrock"])]
+    modloader: Option<Modloaders>,
+
+    /// Install a proxy instead of a server
+    #[arg(long="proxy", conflicts_with="modloader")]
+    proxy: Option<ProxySoftware>,
+
+    /// Install or upgrade a Bedrock Dedicated Server (Linux) instead of a Java server
+    #[arg(long="bedrock", conflicts_with_all=["modloader", "proxy"])]
+    bedrock: bool,
+
+    /// Which build to install: recommended, latest or an exact build (everything but Vanilla)
+    #[arg(short='b', long="build", default_value="recommended")]
+    build: BuildSelection,
+
+    /// Paper/Folia build channel
+    #[arg(long="channel", default_value="default")]
+    channel: PaperChannel,
+
+    /// Fabric/Quilt installer version: recommended, latest or an exact version
+    #[arg(long="installer-version", default_value="recommended")]
+    installer_build: BuildSelection,
+
+    /// Download Fabric's single-file server launcher instead of running the installer
+    #[arg(long="server-launcher")]
+    server_launcher: bool,
+
+    /// Sponge API major version to install a SpongeVanilla/SpongeForge build for, e.g. 8 or 11
+    #[arg(long="sponge-api")]
+    sponge_api: Option<String>,
 
     /// Path to download to
     #[arg(short='p', long="path")]
     path: Option<PathBuf>,
 
-    /// Neoforge Version (only required if --modloader neo-forge)
+    /// Pin an exact NeoForge version, same as --build for --modloader neo-forge
     #[arg(long="neoforge-version")]
     neoforge_ver: Option<String>,
+
+    /// Only use cached metadata, never contact upstream for version lists
+    #[arg(long="offline")]
+    offline: bool,
 }
 
 
 fn main() -> Result<(), LibError>{
     println!("Beginning Download...");
     config_create_config()?;
-    let config = config_read_config()?;
     let args = Args::parse();
-    let versions = meta_fetch_game_versions()?;
-    if !versions.contains(&args.mcversion){
-        eprintln!("Invalid Minecraft Version!");
-        std::process::exit(1);
-    }
-    let path_str;
-    match args.path {
-        Some(path)=>path_str = path.display().to_string(),
-        None => {
-            eprintln!("Invalid path!");
+    cache_set_offline(args.offline);
+    let kind = match (args.bedrock, args.proxy, args.modloader) {
+        (true, _, _) => ServerKind::Bedrock,
+        (false, Some(proxy), _) => ServerKind::Proxy(proxy),
+        (false, None, Some(modloader)) => ServerKind::Java(modloader),
+        (false, None, None) => {
+            eprintln!("Missing modloader!");
             std::process::exit(1);
         }
-    } 
-    let mut neofroge_ver = "".to_owned();
-    match args.neoforge_ver {
-        Some(ver) => neofroge_ver = ver,
-        None => {
-            if args.modloader == Modloaders::NeoForge {
-                eprintln!("No NeoForge Version specified!");
-                std::process::exit(1);
-            }
+    };
+    let installer = installer_for(kind)?;
+    let version = match installer.resolve_version(&args.mcversion) {
+        Ok(ver) => ver,
+        Err(LibError::VersionNotFound { .. }) => {
+            eprintln!("Invalid {} Version!", installer.name());
+            std::process::exit(1);
         }
-    }
-
-    match config_collect_java_bin_path(JavaVersion::Java17) {
-        Ok(_) => println!("Found Java 17..."),
-        Err(_) => {
-            eprintln!("Java 17 was not found!");
-            eprintln!("Downloading Java 17...");
-            let java_dir = config.directories.java_dir;
-            #[cfg(target_os = "linux")]
-            download_java_openjdk_amazon_correto(LINUX_JAVA_17_URL, LINUX_JAVA_17_SHA256, true, java_dir, JavaVersion::Java17)?;
-            #[cfg(target_os = "windows")]
-            download_java_openjdk_amazon_correto(WINDOWS_JAVA_17_URL, WINDOWS_JAVA_17_SHA256, true, java_dir, JavaVersion::Java17)?;
+        Err(e) => return Err(e),
+    };
+    if let GameVersion::Java(mc_version) = &version {
+        let version_kinds = if args.version_kinds.is_empty() {
+            VersionKind::ALL.to_vec()
+        } else {
+            args.version_kinds.clone()
+        };
+        let versions = meta_fetch_game_versions_of_kind(&version_kinds)?;
+        if !versions.contains(mc_version){
+            eprintln!("Invalid Minecraft Version!");
+            std::process::exit(1);
         }
     }
+    let path_str = parse_path(args.path);
+    let build = match args.neoforge_ver {
+        Some(ver) if kind == ServerKind::Java(Modloaders::NeoForge) => BuildSelection::Pinned(ver),
+        _ => args.build,
+    };
 
-    match args.modloader {
-        Modloaders::Vanilla => wrap_download_vanilla_server(args.mcversion, path_str),
-        Modloaders::Forge => wrap_download_forge_server(args.mcversion, path_str),
-        Modloaders::NeoForge => wrap_download_neoforge_server(args.mcversion, path_str, neofroge_ver),
-        Modloaders::Fabric => wrap_download_fabric_server(args.mcversion, path_str),
-        Modloaders::Paper => wrap_download_paper_server(args.mcversion, path_str),
-        Modloaders::Folia => wrap_download_folia_server(args.mcversion, path_str),
+    if let Some(java_version) = installer.java_version(&version)? {
+        println!("{} {version} runs on {java_version}...", installer.name());
+        java_ensure_installed(java_version, &TerminalProgress::new())?;
     }
-    Ok(())
-}
 
-fn wrap_download_vanilla_server(ver: String, path: String) {
-    println!("Downloading Vanilla server.jar...");
-    match download_vanilla_server(ver,path,true) {
-        Ok(_) => {
-            println!("Done!");
-        }
-        Err(e) => {
-            eprintln!("Could not download vanilla server :{e}");
-        }
-    }
-}
-fn wrap_download_forge_server(ver: String, path: String) {
-    println!("Downloading Forge installer.jar...");
-    match download_forge_server(ver,path, true) {
+    let options = InstallOptions {
+        build,
+        installer: args.installer_build,
+        channel: args.channel,
+        server_launcher: args.server_launcher,
+        sponge_api: args.sponge_api,
+    };
+    println!("Installing {}...", installer.name());
+    match installer.install(&version, Path::new(&path_str), &options, &TerminalProgress::new()) {
         Ok(_) => {
             println!("Done!");
         }
         Err(e) => {
-            eprintln!("Could not download/install Forge server :{e}");
-        }
-    }
-}
-fn wrap_download_neoforge_server(ver: String, path: String, neoforge_ver: String) {
-    println!("Downloading NeoForge installer.jar...");
-    match download_neoforge_server(path, ver,true, neoforge_ver) {
-        Ok(_) => {
-            println!("Done!");
-        }
-        Err(e) => {
-            eprintln!("Could not download/install NeoForge server :{e}");
-        }
-    }
-}
-fn wrap_download_fabric_server(ver: String, path: String) {
-    println!("Downloading Fabric installer.jar...");
-    match download_fabric_server(ver,path,true) {
-        Ok(_) => {
-            println!("Done!");
-        }
-        Err(e) => {
-            eprintln!("Could not download/install Fabric server :{e}");
+            eprintln!("Could not download/install {} :{e}", installer.name());
+            return Err(e);
         }
     }
+    Ok(())
 }
-fn wrap_download_paper_server(ver: String, path: String) {
-    println!("Downloading Paper server.jar...");
-    match download_paper_server(ver,path,true, false) {
-        Ok(_) => {
-            println!("Done!");
-        }
-        Err(e) => {
-            eprintln!("Could not download Paper server :{e}");
+
+fn parse_path(path: Option<PathBuf>) -> String {
+    match path {
+        Some(path)=>path.display().to_string(),
+        None => {
+            eprintln!("Invalid path!");
+            std::process::exit(1);
         }
     }
 }
-fn wrap_download_folia_server(ver: String, path: String) {
-    println!("Downloading Folia server.jar...");
-    match download_paper_server(ver,path,true, true) {
-        Ok(_) => {
-            println!("Done!");
-        }
-        Err(e) => {
-            eprintln!("Could not download Folia server :{e}");
-        }
-    }
-}
\ No newline at end of file
@@ -1,16 +1,24 @@
+use std::cmp::Ordering as CmpOrdering;
 use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::fs::{self, File, OpenOptions};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::fmt;
+use std::str::FromStr;
 
 use clap::ValueEnum;
 use thiserror::Error;
 use indicatif::*;
 use serde::*;
 use directories::*;
+use sha1::{Digest, Sha1};
+use md5::Md5;
 use sha256::*;
 use flate2::read::GzDecoder;
 use tar::Archive;
@@ -39,15 +47,792 @@ pub enum LibError {
     Misc(String),
     #[error("Variable Error: {0}")]
     Var(#[from] std::env::VarError),
-    #[error("Zip Error: {0}")]
-    Zip(#[from] zip::result::ZipError),
+    #[error("Download of {url} failed after {attempts} attempt(s): {reason}")]
+    Download {
+        url: String,
+        attempts: u32,
+        reason: String,
+    },
+    #[error("Checksum mismatch for {file}: expected {algorithm} {expected}, got {actual}")]
+    ChecksumMismatch {
+        file: String,
+        algorithm: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("No {loader} build available for Minecraft {version}")]
+    NoBuild {
+        loader: String,
+        version: String,
+    },
+    #[error("{loader} build {build} does not exist for Minecraft {version}")]
+    BuildNotFound {
+        loader: String,
+        version: String,
+        build: String,
+    },
+    #[error("{loader} installer failed ({status}), last output:\n{log_tail}")]
+    InstallerFailed {
+        loader: String,
+        status: String,
+        log_tail: String,
+    },
+    #[error("Offline mode: no cached copy of {0}")]
+    Offline(String),
+    #[error("Size mismatch for {file}: expected {expected} bytes, got {actual} bytes")]
+    SizeMismatch {
+        file: String,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("{loader} has no Minecraft version {version}")]
+    VersionNotFound {
+        loader: String,
+        version: String,
+    },
+    #[error("HTTP {status} from {url}")]
+    HttpStatus {
+        status: u16,
+        url: String,
+    },
+    #[error("{java} was not found at {path}")]
+    JavaNotFound {
+        java: String,
+        path: String,
+    },
+    #[error("Could not parse {file} at line {line}: {message}")]
+    ConfigParse {
+        file: String,
+        line: usize,
+        message: String,
+    },
+    #[error("Could not extract {file}: {reason}")]
+    Archive {
+        file: String,
+        reason: String,
+    },
+    #[error("Unsupported host platform: {0}")]
+    UnsupportedPlatform(String),
+    #[error("{vendor} publishes no {java} build for {platform}")]
+    NoJavaBuild {
+        vendor: String,
+        java: String,
+        platform: String,
+    },
 }
 
+// Status errors carry the URL, they are the ones worth showing to a user.
+fn lib_http_error(url: &str, error: ureq::Error) -> LibError {
+    match error {
+        ureq::Error::StatusCode(status) => LibError::HttpStatus { status, url: url.to_owned() },
+        error => LibError::Net(error),
+    }
+}
+
+
+//
+// Progress Reporting
+//
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    Phase { message: String },
+    Warning { message: String },
+    Download { url: String, downloaded: u64, total: Option<u64> },
+    ChecksumVerified { file: String, algorithm: String },
+    Extracting { file: String },
+    InstallerOutput { line: String },
+    Done,
+    Failed { message: String },
+}
+
+pub trait ProgressReporter: Send + Sync {
+    fn report(&self, event: ProgressEvent);
+}
+
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {
+    fn report(&self, _event: ProgressEvent) {}
+}
+
+// Renders progress on the terminal with indicatif bars and spinners.
+#[derive(Default)]
+pub struct TerminalProgress {
+    state: Mutex<TerminalProgressState>,
+}
+
+#[derive(Default)]
+struct TerminalProgressState {
+    bar: Option<ProgressBar>,
+    url: Option<String>,
+}
+
+impl TerminalProgress {
+    pub fn new() -> TerminalProgress {
+        TerminalProgress::default()
+    }
+}
+
+impl TerminalProgressState {
+    fn finish_bar(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+        }
+        self.url = None;
+    }
+
+    fn spinner(&mut self) -> &ProgressBar {
+        if self.url.is_some() {
+            self.finish_bar();
+        }
+        self.bar.get_or_insert_with(|| {
+            let spinner = ProgressBar::new_spinner();
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            if let Ok(style) = ProgressStyle::default_spinner().template("{spinner} {wide_msg}") {
+                spinner.set_style(style.tick_chars("|/-\\"));
+            }
+            spinner
+        })
+    }
+}
+
+impl ProgressReporter for TerminalProgress {
+    fn report(&self, event: ProgressEvent) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match event {
+            ProgressEvent::Phase { message } => {
+                state.finish_bar();
+                println!("{message}");
+            }
+            ProgressEvent::Warning { message } => {
+                state.finish_bar();
+                eprintln!("{message}");
+            }
+            ProgressEvent::Download { url, downloaded, total } => {
+                if state.url.as_deref() != Some(url.as_str()) {
+                    state.finish_bar();
+                    let bar = ProgressBar::no_length();
+                    let style = match total {
+                        Some(_) => ProgressStyle::default_bar()
+                            .template("{bar:80.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})")
+                            .map(|style| style.progress_chars("=> ")),
+                        None => ProgressStyle::default_spinner()
+                            .template("{spinner} {bytes} ({bytes_per_sec})")
+                            .map(|style| style.tick_chars("|/-\\")),
+                    };
+                    if let Ok(style) = style {
+                        bar.set_style(style);
+                    }
+                    state.bar = Some(bar);
+                    state.url = Some(url);
+                }
+                if let Some(bar) = &state.bar {
+                    if let Some(total) = total {
+                        bar.set_length(total);
+                    }
+                    bar.set_position(downloaded);
+                }
+            }
+            ProgressEvent::ChecksumVerified { algorithm, .. } => {
+                state.finish_bar();
+                println!("Verified {algorithm} checksum.");
+            }
+            ProgressEvent::Extracting { file } => {
+                state.spinner().set_message(format!("Extracting {file}..."));
+            }
+            ProgressEvent::InstallerOutput { line } => {
+                state.spinner().set_message(line);
+            }
+            ProgressEvent::Done => {
+                state.finish_bar();
+                println!("Done!");
+            }
+            ProgressEvent::Failed { message } => {
+                state.finish_bar();
+                eprintln!("{message}");
+            }
+        }
+    }
+}
+
+pub fn progress_phase(reporter: &dyn ProgressReporter, message: impl Into<String>) {
+    reporter.report(ProgressEvent::Phase { message: message.into() });
+}
+
+// Reports the outcome of a whole operation, library functions themselves never send Done/Failed.
+pub fn progress_report_result<T>(reporter: &dyn ProgressReporter, result: &Result<T, LibError>) {
+    match result {
+        Ok(_) => reporter.report(ProgressEvent::Done),
+        Err(e) => reporter.report(ProgressEvent::Failed { message: e.to_string() }),
+    }
+}
 
 //
 // The download Stuff
 //
 
+pub const DOWNLOAD_MAX_ATTEMPTS: u32 = 5;
+pub const DOWNLOAD_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+pub fn download_file(url: &str, dest: &Path, checksum: Option<&Checksum>, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let sources = config_read_sources()?;
+    let mut last_error = None;
+    for candidate in sources.resolve(url) {
+        match download_file_from(&candidate, dest, checksum, reporter) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                reporter.report(ProgressEvent::Warning { message: format!("Download from {candidate} failed: {e}") });
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| LibError::Misc(format!("No source configured for {url}"))))
+}
+
+fn download_file_from(url: &str, dest: &Path, checksum: Option<&Checksum>, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // A leftover .part file is only resumed when it was fetched from the same URL
+    let part_path = download_part_path(dest);
+    let meta_path = download_part_meta_path(&part_path);
+    let stored = fs::read_to_string(&meta_path).unwrap_or_default();
+    if part_path.exists() && stored.lines().next() != Some(url) {
+        fs::remove_file(&part_path)?;
+    }
+
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match download_file_attempt(url, &part_path, reporter) {
+            Ok(()) => break,
+            Err(e) => {
+                if attempts >= DOWNLOAD_MAX_ATTEMPTS || !download_is_transient(&e) {
+                    return Err(LibError::Download {
+                        url: url.to_owned(),
+                        attempts,
+                        reason: e.to_string(),
+                    });
+                }
+                // Exponential backoff: 0.5s, 1s, 2s, 4s, ...
+                sleep(DOWNLOAD_RETRY_BACKOFF * 2u32.pow(attempts - 1));
+            }
+        }
+    }
+
+    if let Some(checksum) = checksum
+        && let Err(e) = download_verify_file(&part_path, checksum) {
+        fs::remove_file(&part_path)?;
+        let _ = fs::remove_file(&meta_path);
+        return Err(match e {
+            LibError::ChecksumMismatch { algorithm, expected, actual, .. } => LibError::ChecksumMismatch {
+                file: dest.to_string_lossy().to_string(),
+                algorithm,
+                expected,
+                actual,
+            },
+            e => e,
+        });
+    }
+
+    if let Some(checksum) = checksum {
+        reporter.report(ProgressEvent::ChecksumVerified {
+            file: dest.to_string_lossy().to_string(),
+            algorithm: checksum.algorithm().to_owned(),
+        });
+    }
+
+    fs::rename(&part_path, dest)?;
+    let _ = fs::remove_file(&meta_path);
+    Ok(())
+}
+
+// Byte progress is only reported every so often, the events may travel over a socket.
+const DOWNLOAD_REPORT_INTERVAL: u64 = 256 * 1024;
+
+fn download_file_attempt(url: &str, part_path: &Path, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let offset = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    let meta_path = download_part_meta_path(part_path);
+
+    let mut request = download_request(url);
+    if offset > 0 {
+        request = request.header("Range", format!("bytes={offset}-"));
+        // If-Range makes the server send the whole body when the resource changed since
+        if let Some(validator) = fs::read_to_string(&meta_path).ok().and_then(|meta| meta.lines().nth(1).filter(|v| !v.is_empty()).map(str::to_owned)) {
+            request = request.header("If-Range", validator);
+        }
+    }
+    let mut response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::StatusCode(416)) if offset > 0 => {
+            // The partial file is no longer valid for this resource, start over
+            fs::remove_file(part_path)?;
+            return download_file_attempt(url, part_path, reporter);
+        }
+        Err(e) => return Err(lib_http_error(url, e)),
+    };
+
+    // The server may ignore the Range header and send the whole body again
+    let resumed = offset > 0 && response.status().as_u16() == 206;
+    let (mut file, mut written) = if resumed {
+        (OpenOptions::new().append(true).open(part_path)?, offset)
+    } else {
+        let headers = response.headers();
+        let validator = headers.get("etag").or_else(|| headers.get("last-modified")).and_then(|v| v.to_str().ok()).unwrap_or_default();
+        fs::write(&meta_path, format!("{url}\n{validator}\n"))?;
+        (File::create(part_path)?, 0)
+    };
+    let total = response.body().content_length().map(|len| len + written);
+
+    let report = |downloaded: u64| {
+        reporter.report(ProgressEvent::Download {
+            url: url.to_owned(),
+            downloaded,
+            total,
+        });
+    };
+    report(written);
+    let mut last_report = written;
+
+    let mut reader = response.body_mut().as_reader();
+    let mut buffer = [0u8; 8 * 1024];
+    loop {
+        // Read errors come from the connection, write errors from the local disk
+        let n = reader.read(&mut buffer).map_err(|e| LibError::Net(ureq::Error::Io(e)))?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buffer[..n])?;
+        written += n as u64;
+        if written - last_report >= DOWNLOAD_REPORT_INTERVAL {
+            report(written);
+            last_report = written;
+        }
+    }
+    report(written);
+    file.sync_all()?;
+
+    if let Some(total) = total
+        && written < total {
+        return Err(LibError::Net(ureq::Error::Io(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!("connection closed after {written} of {total} bytes"),
+        ))));
+    }
+    Ok(())
+}
+
+// minecraft.net stalls requests without a user agent it accepts, so every request identifies the manager.
+const DOWNLOAD_USER_AGENT: &str = concat!("mc-server-manager/", env!("CARGO_PKG_VERSION"));
+
+fn download_request(url: &str) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
+    ureq::get(url).header("User-Agent", DOWNLOAD_USER_AGENT)
+}
+
+fn download_part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+// Next to the .part file: the URL it came from and the ETag or Last-Modified to resume against.
+fn download_part_meta_path(part_path: &Path) -> PathBuf {
+    let mut name = part_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".meta");
+    part_path.with_file_name(name)
+}
+
+pub fn download_fetch_text(url: &str) -> Result<String, LibError> {
+    let sources = config_read_sources()?;
+    let mut last_error = None;
+    for candidate in sources.resolve(url) {
+        match download_request(&candidate).call() {
+            Ok(mut response) => return Ok(response.body_mut().read_to_string()?),
+            Err(e) => last_error = Some(lib_http_error(&candidate, e)),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| LibError::Misc(format!("No source configured for {url}"))))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Checksum {
+    Md5(String),
+    Sha1(String),
+    Sha256(String),
+}
+
+impl Checksum {
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            Checksum::Md5(_) => "MD5",
+            Checksum::Sha1(_) => "SHA-1",
+            Checksum::Sha256(_) => "SHA-256",
+        }
+    }
+
+    pub fn expected(&self) -> &str {
+        match self {
+            Checksum::Md5(hash) | Checksum::Sha1(hash) | Checksum::Sha256(hash) => hash,
+        }
+    }
+}
+
+pub fn download_verify_file(path: &Path, checksum: &Checksum) -> Result<(), LibError> {
+    let actual = match checksum {
+        Checksum::Md5(_) => {
+            let mut file = File::open(path)?;
+            let mut hasher = Md5::new();
+            std::io::copy(&mut file, &mut hasher)?;
+            format!("{:x}", hasher.finalize())
+        }
+        Checksum::Sha1(_) => {
+            let mut file = File::open(path)?;
+            let mut hasher = Sha1::new();
+            std::io::copy(&mut file, &mut hasher)?;
+            format!("{:x}", hasher.finalize())
+        }
+        Checksum::Sha256(_) => try_digest(path)?,
+    };
+    let expected = checksum.expected().trim().to_lowercase();
+    if actual != expected {
+        return Err(LibError::ChecksumMismatch {
+            file: path.to_string_lossy().to_string(),
+            algorithm: checksum.algorithm().to_owned(),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+fn download_is_transient(error: &LibError) -> bool {
+    // Local IO errors (disk full, permissions) won't go away by downloading again
+    match error {
+        LibError::HttpStatus { status, .. } => *status == 408 || *status == 429 || *status >= 500,
+        LibError::Net(ureq::Error::Io(_))
+        | LibError::Net(ureq::Error::Timeout(_))
+        | LibError::Net(ureq::Error::HostNotFound)
+        | LibError::Net(ureq::Error::ConnectionFailed)
+        | LibError::Net(ureq::Error::Protocol(_)) => true,
+        _ => false,
+    }
+}
+
+//
+// Upstream Sources
+//
+
+pub const MOJANG_META_URL: &str = "https://piston-meta.mojang.com";
+pub const MOJANG_DATA_URL: &str = "https://piston-data.mojang.com";
+pub const PAPER_API_URL: &str = "https://api.papermc.io";
+pub const FORGE_FILES_URL: &str = "https://files.minecraftforge.net";
+pub const FORGE_MAVEN_URL: &str = "https://maven.minecraftforge.net";
+pub const NEOFORGE_MAVEN_URL: &str = "https://maven.neoforged.net";
+pub const FABRIC_MAVEN_URL: &str = "https://maven.fabricmc.net";
+pub const FABRIC_META_URL: &str = "https://meta.fabricmc.net";
+pub const CORRETTO_URL: &str = "https://corretto.aws";
+pub const ADOPTIUM_API_URL: &str = "https://api.adoptium.net";
+pub const AZUL_API_URL: &str = "https://api.azul.com";
+pub const AZUL_CDN_URL: &str = "https://cdn.azul.com";
+pub const PURPUR_API_URL: &str = "https://api.purpurmc.org";
+pub const PUFFERFISH_CI_URL: &str = "https://ci.pufferfish.host";
+pub const BUNGEECORD_CI_URL: &str = "https://ci.md-5.net";
+pub const QUILT_META_URL: &str = "https://meta.quiltmc.org";
+pub const QUILT_MAVEN_URL: &str = "https://maven.quiltmc.org";
+pub const BEDROCK_LINKS_URL: &str = "https://net-secondary.web.minecraft-services.net";
+pub const BEDROCK_DOWNLOAD_URL: &str = "https://www.minecraft.net";
+pub const SPONGE_API_URL: &str = "https://dl-api.spongepowered.org";
+pub const SPONGE_REPO_URL: &str = "https://repo.spongepowered.org";
+pub const MOHIST_API_URL: &str = "https://mohistmc.com";
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+pub const GITHUB_URL: &str = "https://github.com";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Source {
+    pub url: String,
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+}
+
+impl Source {
+    fn new(url: &str) -> Source {
+        Source {
+            url: url.to_owned(),
+            mirrors: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Sources {
+    pub mojang_meta: Source,
+    pub mojang_data: Source,
+    pub paper: Source,
+    pub forge_files: Source,
+    pub forge_maven: Source,
+    pub neoforge_maven: Source,
+    pub fabric_maven: Source,
+    pub fabric_meta: Source,
+    pub corretto: Source,
+    pub adoptium: Source,
+    pub azul_api: Source,
+    pub azul_cdn: Source,
+    pub purpur: Source,
+    pub pufferfish: Source,
+    pub bungeecord: Source,
+    pub quilt_meta: Source,
+    pub quilt_maven: Source,
+    pub bedrock_links: Source,
+    pub bedrock_download: Source,
+    pub sponge_api: Source,
+    pub sponge_repo: Source,
+    pub mohist: Source,
+    pub github_api: Source,
+    pub github: Source,
+}
+
+impl Default for Sources {
+    fn default() -> Sources {
+        Sources {
+            mojang_meta: Source::new(MOJANG_META_URL),
+            mojang_data: Source::new(MOJANG_DATA_URL),
+            paper: Source::new(PAPER_API_URL),
+            forge_files: Source::new(FORGE_FILES_URL),
+            forge_maven: Source::new(FORGE_MAVEN_URL),
+            neoforge_maven: Source::new(NEOFORGE_MAVEN_URL),
+            fabric_maven: Source::new(FABRIC_MAVEN_URL),
+            fabric_meta: Source::new(FABRIC_META_URL),
+            corretto: Source::new(CORRETTO_URL),
+            adoptium: Source::new(ADOPTIUM_API_URL),
+            azul_api: Source::new(AZUL_API_URL),
+            azul_cdn: Source::new(AZUL_CDN_URL),
+            purpur: Source::new(PURPUR_API_URL),
+            pufferfish: Source::new(PUFFERFISH_CI_URL),
+            bungeecord: Source::new(BUNGEECORD_CI_URL),
+            quilt_meta: Source::new(QUILT_META_URL),
+            quilt_maven: Source::new(QUILT_MAVEN_URL),
+            bedrock_links: Source::new(BEDROCK_LINKS_URL),
+            bedrock_download: Source::new(BEDROCK_DOWNLOAD_URL),
+            sponge_api: Source::new(SPONGE_API_URL),
+            sponge_repo: Source::new(SPONGE_REPO_URL),
+            mohist: Source::new(MOHIST_API_URL),
+            github_api: Source::new(GITHUB_API_URL),
+            github: Source::new(GITHUB_URL),
+        }
+    }
+}
+
+impl Sources {
+    // Maps a URL on one of the default upstreams onto the configured base URL followed by its mirrors.
+    // URLs that don't belong to a known upstream are returned unchanged.
+    pub fn resolve(&self, url: &str) -> Vec<String> {
+        let upstreams = [
+            (MOJANG_META_URL, &self.mojang_meta),
+            (MOJANG_DATA_URL, &self.mojang_data),
+            (PAPER_API_URL, &self.paper),
+            (FORGE_FILES_URL, &self.forge_files),
+            (FORGE_MAVEN_URL, &self.forge_maven),
+            (NEOFORGE_MAVEN_URL, &self.neoforge_maven),
+            (FABRIC_MAVEN_URL, &self.fabric_maven),
+            (FABRIC_META_URL, &self.fabric_meta),
+            (CORRETTO_URL, &self.corretto),
+            (ADOPTIUM_API_URL, &self.adoptium),
+            (AZUL_API_URL, &self.azul_api),
+            (AZUL_CDN_URL, &self.azul_cdn),
+            (PURPUR_API_URL, &self.purpur),
+            (PUFFERFISH_CI_URL, &self.pufferfish),
+            (BUNGEECORD_CI_URL, &self.bungeecord),
+            (QUILT_META_URL, &self.quilt_meta),
+            (QUILT_MAVEN_URL, &self.quilt_maven),
+            (BEDROCK_LINKS_URL, &self.bedrock_links),
+            (BEDROCK_DOWNLOAD_URL, &self.bedrock_download),
+            (SPONGE_API_URL, &self.sponge_api),
+            (SPONGE_REPO_URL, &self.sponge_repo),
+            (MOHIST_API_URL, &self.mohist),
+            (GITHUB_API_URL, &self.github_api),
+            (GITHUB_URL, &self.github),
+        ];
+        for (default_base, source) in upstreams {
+            if let Some(rest) = url.strip_prefix(default_base) {
+                return std::iter::once(&source.url)
+                    .chain(source.mirrors.iter())
+                    .map(|base| format!("{}{}", base.trim_end_matches('/'), rest))
+                    .collect();
+            }
+        }
+        vec![url.to_owned()]
+    }
+}
+
+//
+// Metadata Cache
+//
+
+pub const CACHE_DEFAULT_TTL_SECS: u64 = 60 * 60;
+
+static CACHE_FORCE_OFFLINE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CacheSettings {
+    pub offline: bool,
+    pub ttl_secs: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> CacheSettings {
+        CacheSettings {
+            offline: false,
+            ttl_secs: CACHE_DEFAULT_TTL_SECS,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CacheEntryMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+    // The rel="next" page from the Link header of paginated APIs like GitHub's
+    #[serde(default)]
+    next: Option<String>,
+}
+
+// Overrides the `offline` config setting for the rest of the process, e.g. from a `--offline` flag.
+pub fn cache_set_offline(offline: bool) {
+    CACHE_FORCE_OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+// Marks the cached copy of a URL as stale, the next fetch revalidates it with upstream regardless of the TTL.
+pub fn cache_expire(url: &str) -> Result<(), LibError> {
+    let config = config_read_config_or_default()?;
+    let meta_path = PathBuf::from(&config.directories.cache_dir).join("meta").join(format!("{}.json", digest(url)));
+    let Ok(text) = fs::read_to_string(&meta_path) else {
+        return Ok(());
+    };
+    let mut meta: CacheEntryMeta = serde_json::from_str(&text)?;
+    meta.fetched_at = 0;
+    fs::write(&meta_path, serde_json::to_string(&meta)?)?;
+    Ok(())
+}
+
+pub fn cache_fetch_text(url: &str) -> Result<String, LibError> {
+    cache_fetch_entry(url).map(|(body, _)| body)
+}
+
+// Every page of a paginated listing, following the Link headers from the first URL on.
+pub fn cache_fetch_pages(url: &str) -> Result<Vec<String>, LibError> {
+    let mut pages = Vec::new();
+    let mut seen = Vec::new();
+    let mut next = Some(url.to_owned());
+    while let Some(page_url) = next.take() {
+        if seen.contains(&page_url) {
+            break;
+        }
+        let (body, page_next) = cache_fetch_entry(&page_url)?;
+        pages.push(body);
+        seen.push(page_url);
+        next = page_next;
+    }
+    Ok(pages)
+}
+
+fn cache_fetch_entry(url: &str) -> Result<(String, Option<String>), LibError> {
+    let config = config_read_config_or_default()?;
+    let offline = config.cache.offline || CACHE_FORCE_OFFLINE.load(Ordering::Relaxed);
+
+    let cache_dir = PathBuf::from(&config.directories.cache_dir).join("meta");
+    let key = digest(url);
+    let body_path = cache_dir.join(format!("{key}.body"));
+    let meta_path = cache_dir.join(format!("{key}.json"));
+
+    let cached = match (fs::read_to_string(&body_path), fs::read_to_string(&meta_path)) {
+        (Ok(body), Ok(meta)) => serde_json::from_str::<CacheEntryMeta>(&meta).ok().map(|meta| (body, meta)),
+        _ => None,
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+    if let Some((body, meta)) = &cached
+        && (offline || now.saturating_sub(meta.fetched_at) < config.cache.ttl_secs) {
+        return Ok((body.clone(), meta.next.clone()));
+    }
+    if offline {
+        return Err(LibError::Offline(url.to_owned()));
+    }
+
+    let mut last_error = None;
+    for candidate in config.sources.resolve(url) {
+        let mut request = download_request(&candidate);
+        if let Some((_, meta)) = &cached {
+            if let Some(etag) = &meta.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &meta.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+
+        let mut response = match request.call() {
+            Ok(response) => response,
+            Err(e) => {
+                last_error = Some(lib_http_error(&candidate, e));
+                continue;
+            }
+        };
+        let header = |name: &str| {
+            response.headers().get(name).and_then(|value| value.to_str().ok()).map(|value| value.to_owned())
+        };
+        let mut etag = header("etag");
+        let mut last_modified = header("last-modified");
+        let mut next = header("link").and_then(|link| cache_link_next(&link));
+
+        // A 304 doesn't have to repeat the validators, the stored ones still describe the cached body
+        let body = if response.status().as_u16() == 304
+            && let Some((body, meta)) = &cached {
+            etag = etag.or_else(|| meta.etag.clone());
+            last_modified = last_modified.or_else(|| meta.last_modified.clone());
+            next = next.or_else(|| meta.next.clone());
+            body.clone()
+        } else {
+            response.body_mut().read_to_string()?
+        };
+
+        let meta = CacheEntryMeta {
+            url: url.to_owned(),
+            etag,
+            last_modified,
+            fetched_at: now,
+            next,
+        };
+        fs::create_dir_all(&cache_dir)?;
+        fs::write(&body_path, &body)?;
+        fs::write(&meta_path, serde_json::to_string(&meta)?)?;
+        return Ok((body, meta.next));
+    }
+
+    // Upstream is unreachable, a stale copy is better than nothing
+    if let Some((body, meta)) = cached {
+        return Ok((body, meta.next));
+    }
+    Err(last_error.unwrap_or_else(|| LibError::Misc(format!("No source configured for {url}"))))
+}
+
+// Link: <https://api.github.com/...&page=2>; rel="next", <https://api.github.com/...&page=5>; rel="last"
+fn cache_link_next(link: &str) -> Option<String> {
+    link.split(',')
+        .find(|part| part.contains("rel=\"next\""))
+        .and_then(|part| part.split_once('<'))
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map(|(url, _)| url.to_owned())
+}
+
 //
 // Vanilla Server
 //
@@ -83,6 +868,19 @@ struct MojangMinecraftVersion {
     downloads: MojangDownloads,
 }
 
+// Parsed separately from MojangMinecraftVersion, old versions have no server download but still need Java.
+#[derive(Deserialize, Debug)]
+struct MojangJavaRequirement {
+    #[serde(rename = "javaVersion")]
+    java_version: Option<MojangJavaVersion>,
+}
+
+#[derive(Deserialize, Debug)]
+struct MojangJavaVersion {
+    #[serde(rename = "majorVersion")]
+    major_version: u32,
+}
+
 #[derive(Deserialize, Debug)]
 struct MojangDownloads {
     server: MojangServerDownload,
@@ -91,695 +889,3049 @@ struct MojangDownloads {
 #[derive(Deserialize, Debug)]
 struct MojangServerDownload {
     url: String,
+    sha1: String,
+    size: u64,
 }
 
-pub fn download_vanilla_server(ver: String, path:String, term: bool) -> Result<(), LibError>{
+pub fn download_vanilla_server(ver: &MinecraftVersion, path:String, reporter: &dyn ProgressReporter) -> Result<(), LibError>{
 
-    let intermediate_url = download_vanilla_get_version_data_url(ver.clone())?;
+    let intermediate_url = download_vanilla_get_version_data_url(ver)?;
     if intermediate_url != "none" {
-
-        //The Download
-        let downlad_url = download_vanilla_get_version_download_url(intermediate_url)?;
-
-        let mut response = ureq::get(downlad_url).call()?;
-
-        let size = response.body().content_length().unwrap_or(0);
-        
-        let mut reader = response.body_mut().as_reader();
+        let download = download_vanilla_get_version_download(intermediate_url)?;
+        let checksum = Checksum::Sha1(download.sha1);
         let save_path = Path::new(&path).join("server.jar");
-        let mut server_jar = File::create(save_path)?;
-
-        let progress = if term {
-            Some(ProgressBar::new(size))
-        } else {
-            None
-        };        
-
-        if let Some(pb) = &progress {
-            pb.set_style(
-                ProgressStyle::default_bar()
-                .template(
-                    "{bar:80.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})"
-                )
-                .unwrap()
-                .progress_chars("=> "),
-            );
 
+        if download_vanilla_is_up_to_date(&save_path, download.size, &checksum)? {
+            progress_phase(reporter, "server.jar is already up to date, skipping download.");
+            return Ok(());
         }
 
-        let mut buffer = [0u8; 8 * 1024];
-        loop {
-            let n = reader.read(&mut buffer)?;
-            if n == 0 {
-                break;
-            }
-            server_jar.write_all(&buffer[..n])?;
-            if let Some(pb) = &progress {
-                pb.inc(n as u64);
-            }
+        download_file(&download.url, &save_path, Some(&checksum), reporter)?;
+
+        let actual = fs::metadata(&save_path)?.len();
+        if actual != download.size {
+            fs::remove_file(&save_path)?;
+            return Err(LibError::SizeMismatch {
+                file: save_path.to_string_lossy().to_string(),
+                expected: download.size,
+                actual,
+            });
         }
         Ok(())
     } else {
-        return Err(LibError::Ver(ver));
+        Err(LibError::VersionNotFound {
+            loader: "Vanilla".to_owned(),
+            version: ver.to_string(),
+        })
     }
 }
 
-fn download_vanilla_get_version_data_url(version: String) -> Result<String, LibError> {
+fn download_vanilla_is_up_to_date(jar: &Path, size: u64, checksum: &Checksum) -> Result<bool, LibError> {
+    if !jar.exists() || fs::metadata(jar)?.len() != size {
+        return Ok(false);
+    }
+    Ok(download_verify_file(jar, checksum).is_ok())
+}
+
+fn download_vanilla_get_version_data_url(version: &MinecraftVersion) -> Result<String, LibError> {
 
     let manifest = download_vanilla_fetch_available_vannila_versions()?;
     let mut return_data = "none".to_owned();    
 
     for manifest_version in manifest.versions {
-        if manifest_version.id == version {
+        if manifest_version.id == version.id() {
             return_data = manifest_version.url;
         } 
     }
-    return Ok(return_data);
+    Ok(return_data)
 }
 
-fn download_vanilla_get_version_download_url(data_url: String) -> Result<String, LibError> {
-    let mut response = ureq::get(data_url).call()?;
-    let body = response.body_mut();
-    let text = body.read_to_string()?;
+fn download_vanilla_get_version_download(data_url: String) -> Result<MojangServerDownload, LibError> {
+    let text = cache_fetch_text(&data_url)?;
     let version_data: MojangMinecraftVersion = serde_json::from_str(&text)?;
-    return Ok(version_data.downloads.server.url);
+    Ok(version_data.downloads.server)
+}
+
+pub fn download_vanilla_fetch_available_vannila_versions() -> Result<MojangVersionManifest, LibError> {
+    let text = cache_fetch_text(&format!("{MOJANG_META_URL}/mc/game/version_manifest_v2.json"))?;
+
+    let manifest: MojangVersionManifest = serde_json::from_str(&text)?;
+    Ok(manifest)
 }
 
-fn download_vanilla_fetch_available_vannila_versions() -> Result<MojangVersionManifest, LibError> {
-    let mut response = ureq::get("https://piston-meta.mojang.com/mc/game/version_manifest_v2.json")
-    .call()?;
+//
+// Installers
+//
+
+// Output of the Forge/NeoForge/Fabric/Quilt installers ends up here, inside the instance directory.
+pub const INSTALLER_LOG_NAME: &str = "installer-output.log";
+const INSTALLER_LOG_TAIL_LINES: usize = 30;
 
-    let body = response.body_mut();
-    let text = body.read_to_string()?;
+fn download_run_installer(loader: &str, java_cmd: &str, args: &[&str], path: &str, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let mut log = File::create(Path::new(path).join(INSTALLER_LOG_NAME))?;
+
+    let mut child = Command::new(java_cmd)
+        .args(args)
+        .current_dir(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain stdout and stderr on their own threads so neither pipe can fill up and block the installer
+    let (sender, receiver) = mpsc::channel::<String>();
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let sender = sender.clone();
+        readers.push(std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let _ = sender.send(line);
+            }
+        }));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let sender = sender.clone();
+        readers.push(std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                let _ = sender.send(line);
+            }
+        }));
+    }
+    drop(sender);
+
+    let mut tail: VecDeque<String> = VecDeque::with_capacity(INSTALLER_LOG_TAIL_LINES);
+    for line in receiver {
+        writeln!(log, "{line}")?;
+        reporter.report(ProgressEvent::InstallerOutput { line: line.clone() });
+        if tail.len() == INSTALLER_LOG_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+    let status = child.wait()?;
+    log.flush()?;
+
+    if !status.success() {
+        return Err(LibError::InstallerFailed {
+            loader: loader.to_owned(),
+            status: status.to_string(),
+            log_tail: Vec::from(tail).join("\n"),
+        });
+    }
 
-    let manifest: MojangVersionManifest = serde_json::from_str(&text).unwrap();
-    return Ok(manifest);
+    for leftover in ["installer.jar", "installer.jar.log"] {
+        let leftover = Path::new(path).join(leftover);
+        if leftover.exists() {
+            fs::remove_file(leftover)?;
+        }
+    }
+    Ok(())
 }
 
 //
 // Forge Server
 //
 
-pub fn download_forge_server(ver: String, path:String, term: bool) -> Result<(), LibError>{
-    let forge_ver = meta_get_forge_version_for_corresponding_mc_version(ver.clone())?;
-    download_forge_installer(ver, path.clone(), forge_ver, term)?;
+pub fn download_forge_server(ver: &MinecraftVersion, path:String, build: &BuildSelection, reporter: &dyn ProgressReporter) -> Result<(), LibError>{
+    let java_cmd = java_resolve_bin_path(ver, Modloaders::Forge, reporter)?;
+    let forge_build = meta_resolve_forge_build(ver, build)?;
+    progress_phase(reporter, format!("Selected Forge build {forge_build}"));
+    download_forge_installer(path.clone(), forge_build, reporter)?;
 
-    if term {
-        println!("Installing Forge Server...");
-    }
+    progress_phase(reporter, "Installing Forge Server...");
 
-    let spinner = if term {
-            Some(ProgressBar::new_spinner())
-        } else {
-            None
-    };
+    download_run_installer("Forge", &java_cmd, &["-jar", "installer.jar", "--installServer"], &path, reporter)
+}
+
+// Forge build ids are full maven versions, e.g. 1.20.1-47.2.0 or 1.7.10-10.13.4.1614-1.7.10
+fn download_forge_installer(path:String, forge_build: String, reporter: &dyn ProgressReporter) -> Result<(), LibError>{
+    let url = format!("{FORGE_MAVEN_URL}/net/minecraftforge/forge/{forge_build}/forge-{forge_build}-installer.jar");
+    download_file(&url, &Path::new(&path).join("installer.jar"), None, reporter)
+}
+
+//
+// Neoforge Server
+//
+
+pub fn download_neoforge_server(path: String, ver: &MinecraftVersion, reporter: &dyn ProgressReporter, build: &BuildSelection) -> Result<(), LibError> {
+    let java_cmd = java_resolve_bin_path(ver, Modloaders::NeoForge, reporter)?;
+    let neoforge_ver = meta_resolve_neoforge_build(ver, build)?;
+    progress_phase(reporter, format!("Selected NeoForge build {neoforge_ver}"));
+    download_neoforge_installer(neoforge_ver, path.clone(), reporter)?;
+
+    progress_phase(reporter, "Installing NeoForge Server...");
+
+    download_run_installer("NeoForge", &java_cmd, &["-jar", "installer.jar", "--installServer"], &path, reporter)
+}
+
+fn download_neoforge_installer(neoforge_ver: String, path: String, reporter: &dyn ProgressReporter) -> Result<(), LibError>{
+    let url = format!("{NEOFORGE_MAVEN_URL}/releases/net/neoforged/neoforge/{neoforge_ver}/neoforge-{neoforge_ver}-installer.jar");
+    download_file(&url, &Path::new(&path).join("installer.jar"), None, reporter)
+}
+
+//
+// Fabric Server
+//
+
+#[derive(Clone, Debug, Default)]
+pub struct FabricOptions {
+    pub loader: BuildSelection,
+    pub installer: BuildSelection,
+    // Download Fabric's single-file server launcher instead of running the installer
+    pub server_launcher: bool,
+}
+
+pub fn download_fabric_server(mc_ver: &MinecraftVersion, path: String, options: &FabricOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let loader = meta_resolve_fabric_loader(mc_ver, &options.loader)?;
+    let installer = meta_resolve_fabric_installer(&options.installer)?;
+    progress_phase(reporter, format!("Selected Fabric loader {} with installer {}", loader.version, installer.version));
+
+    if options.server_launcher {
+        let url = format!("{FABRIC_META_URL}/v2/versions/loader/{mc_ver}/{}/{}/server/jar", loader.version, installer.version);
+        return download_file(&url, &Path::new(&path).join("server.jar"), None, reporter);
+    }
+
+    let java_cmd = java_resolve_bin_path(mc_ver, Modloaders::Fabric, reporter)?;
+    download_file(&installer.url, &Path::new(&path).join("installer.jar"), None, reporter)?;
+    progress_phase(reporter, "Installing Fabric Server...");
+
+    // The installer runs inside the instance directory, fabric-server-launch.jar needs the vanilla server.jar next to it
+    download_run_installer("Fabric", &java_cmd, &["-jar", "installer.jar", "server", "-mcversion", mc_ver.id(), "-loader", &loader.version, "-dir", ".", "-downloadMinecraft"], &path, reporter)
+}
+
+//
+// Quilt Server
+//
+
+#[derive(Clone, Debug, Default)]
+pub struct QuiltOptions {
+    pub loader: BuildSelection,
+    pub installer: BuildSelection,
+}
+
+pub fn download_quilt_server(mc_ver: &MinecraftVersion, path: String, options: &QuiltOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let loader = meta_resolve_quilt_loader(mc_ver, &options.loader)?;
+    let installer = meta_resolve_quilt_installer(&options.installer)?;
+    progress_phase(reporter, format!("Selected Quilt loader {} with installer {}", loader.version, installer.version));
+
+    let java_cmd = java_resolve_bin_path(mc_ver, Modloaders::Quilt, reporter)?;
+    download_file(&installer.url, &Path::new(&path).join("installer.jar"), None, reporter)?;
+    progress_phase(reporter, "Installing Quilt Server...");
+
+    // The installer runs inside the instance directory
+    download_run_installer("Quilt", &java_cmd, &["-jar", "installer.jar", "install", "server", mc_ver.id(), &loader.version, "--install-dir=.", "--download-server"], &path, reporter)
+}
+
+//
+// Paper Server
+//
+
+#[derive(Debug, Deserialize)]
+pub struct PaperProjectVersions {
+    pub project_id: String,
+    pub project_name: String,
+    pub versions: Vec<String>,
+    pub builds: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaperVersionBuilds {
+    pub project_id: String,
+    pub version: String,
+    pub builds: Vec<PaperBuild>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PaperBuild {
+    pub build: u32,
+    pub channel: String,
+    pub downloads: PaperBuildDownloads,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PaperBuildDownloads {
+    pub application: PaperDownload,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PaperDownload {
+    pub name: String,
+    pub sha256: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PaperChannel {
+    // Only builds the Paper team marks as stable
+    #[default]
+    Default,
+    // Also allow experimental builds, e.g. for freshly released Minecraft versions
+    Experimental,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PaperOptions {
+    pub build: BuildSelection,
+    pub channel: PaperChannel,
+}
+
+pub fn download_paper_server(ver: &MinecraftVersion, path:String, options: &PaperOptions, reporter: &dyn ProgressReporter, folia: bool) -> Result<(), LibError>{
+    let build = meta_resolve_paper_build(ver, folia, options)?;
+    progress_phase(reporter, format!("Selected {} build {} ({})", download_paper_project_name(folia), build.build, build.channel));
+    download_paper_install_build(ver, &path, &build, reporter, folia)
+}
+
+// Moves a Paper/Folia instance to the newest build for its Minecraft version, returns false if it already is up to date.
+pub fn download_paper_update_server(path: String, channel: PaperChannel, reporter: &dyn ProgressReporter) -> Result<bool, LibError> {
+    let instance = instance_read(Path::new(&path))?;
+    let folia = match instance.loader {
+        Modloaders::Paper => false,
+        Modloaders::Folia => true,
+        loader => return Err(LibError::Misc(format!("Only Paper and Folia instances can be updated, {path} is {loader:?}"))),
+    };
+
+    let options = PaperOptions {
+        build: BuildSelection::Latest,
+        channel,
+    };
+    // A build list younger than the cache TTL can still miss the newest build
+    cache_expire(&download_paper_builds_url(&instance.mc_version, folia))?;
+    let build = meta_resolve_paper_build(&instance.mc_version, folia, &options)?;
+    // An instance installed from a newer channel may already be past the newest build of this one, never downgrade
+    let installed = instance.build.as_deref().and_then(|build| build.parse::<u32>().ok());
+    if let Some(installed) = installed
+        && installed >= build.build {
+        progress_phase(reporter, format!("Already up to date (build {installed}, newest {channel:?} build is {}).", build.build));
+        return Ok(false);
+    }
+    progress_phase(reporter, format!("Updating from build {} to {}...", instance.build.as_deref().unwrap_or("unknown"), build.build));
+    download_paper_install_build(&instance.mc_version, &path, &build, reporter, folia)?;
+    Ok(true)
+}
+
+fn download_paper_install_build(ver: &MinecraftVersion, path: &str, build: &PaperBuild, reporter: &dyn ProgressReporter, folia: bool) -> Result<(), LibError> {
+    let project_name = download_paper_project_name(folia);
+    let application = &build.downloads.application;
+    let downlad_url = format!("{PAPER_API_URL}/v2/projects/{project_name}/versions/{ver}/builds/{}/downloads/{}", build.build, application.name);
+    let checksum = Checksum::Sha256(application.sha256.clone());
+    download_file(&downlad_url, &Path::new(path).join("server.jar"), Some(&checksum), reporter)?;
+
+    let loader = if folia { Modloaders::Folia } else { Modloaders::Paper };
+    instance_write(Path::new(path), &Instance::new(loader, ver.clone(), Some(build.build.to_string())))
+}
+
+// Builds are filtered by channel first, recommended and latest both pick the newest remaining build.
+pub fn meta_resolve_paper_build(ver: &MinecraftVersion, folia: bool, options: &PaperOptions) -> Result<PaperBuild, LibError> {
+    let loader = if folia { "Folia" } else { "Paper" };
+
+    let project = download_paper_fetch_versions(folia)?;
+    if !project.versions.iter().any(|v| v == ver.id()) {
+        return Err(LibError::NoBuild {
+            loader: loader.to_owned(),
+            version: ver.to_string(),
+        });
+    }
+
+    let text = cache_fetch_text(&download_paper_builds_url(ver, folia))?;
+    let mut builds = serde_json::from_str::<PaperVersionBuilds>(&text)?.builds;
+    builds.sort_by_key(|build| build.build);
+
+    match &options.build {
+        BuildSelection::Pinned(pinned) => builds
+            .into_iter()
+            .find(|build| build.build.to_string() == *pinned)
+            .ok_or_else(|| LibError::BuildNotFound {
+                loader: loader.to_owned(),
+                version: ver.to_string(),
+                build: pinned.clone(),
+            }),
+        BuildSelection::Recommended | BuildSelection::Latest => builds
+            .into_iter()
+            .rev()
+            .find(|build| options.channel == PaperChannel::Experimental || build.channel == "default")
+            .ok_or_else(|| LibError::NoBuild {
+                loader: loader.to_owned(),
+                version: ver.to_string(),
+            }),
+    }
+}
+
+fn download_paper_builds_url(ver: &MinecraftVersion, folia: bool) -> String {
+    format!("{PAPER_API_URL}/v2/projects/{}/versions/{ver}/builds", download_paper_project_name(folia))
+}
+
+fn download_paper_project_name(folia: bool) -> &'static str {
+    if folia { "folia" } else { "paper" }
+}
+
+pub fn download_paper_fetch_versions(folia: bool) -> Result<PaperProjectVersions, LibError> {
+    download_paper_fetch_project(download_paper_project_name(folia))
+}
+
+// Any project on the PaperMC API, the proxies live there as well.
+pub fn download_paper_fetch_project(project_name: &str) -> Result<PaperProjectVersions, LibError> {
+    let text = cache_fetch_text(&format!("{PAPER_API_URL}/v2/projects/{project_name}"))?;
+
+    let project: PaperProjectVersions = serde_json::from_str(&text)?;
+    Ok(project)
+}
+
+//
+// Purpur Server
+//
+
+#[derive(Debug, Deserialize)]
+pub struct PurpurProject {
+    pub project: String,
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PurpurVersion {
+    pub project: String,
+    pub version: String,
+    pub builds: PurpurVersionBuilds,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PurpurVersionBuilds {
+    pub latest: String,
+    pub all: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PurpurBuild {
+    pub build: String,
+    pub result: String,
+    pub md5: Option<String>,
+}
+
+pub fn download_purpur_server(ver: &MinecraftVersion, path: String, build: &BuildSelection, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let build = meta_resolve_purpur_build(ver, build)?;
+    progress_phase(reporter, format!("Selected Purpur build {}", build.build));
+
+    let url = format!("{PURPUR_API_URL}/v2/purpur/{ver}/{}/download", build.build);
+    let checksum = match &build.md5 {
+        Some(md5) => Some(Checksum::Md5(md5.clone())),
+        None => {
+            reporter.report(ProgressEvent::Warning { message: format!("Purpur build {} has no MD5, the server jar can't be verified", build.build) });
+            None
+        }
+    };
+    download_file(&url, &Path::new(&path).join("server.jar"), checksum.as_ref(), reporter)?;
+
+    instance_write(Path::new(&path), &Instance::new(Modloaders::Purpur, ver.clone(), Some(build.build)))
+}
+
+pub const PURPUR_RECOMMENDED_MAX_BUILDS: usize = 10;
+
+// Recommended skips builds that failed on Purpur's CI, latest takes whatever Purpur lists as latest.
+pub fn meta_resolve_purpur_build(ver: &MinecraftVersion, selection: &BuildSelection) -> Result<PurpurBuild, LibError> {
+    let no_build = || LibError::NoBuild {
+        loader: "Purpur".to_owned(),
+        version: ver.to_string(),
+    };
+    let project = download_purpur_fetch_versions()?;
+    if !project.versions.iter().any(|v| v == ver.id()) {
+        return Err(no_build());
+    }
+
+    let text = cache_fetch_text(&format!("{PURPUR_API_URL}/v2/purpur/{ver}"))?;
+    let version: PurpurVersion = serde_json::from_str(&text)?;
+
+    match selection {
+        BuildSelection::Pinned(pinned) => {
+            if !version.builds.all.contains(pinned) {
+                return Err(LibError::BuildNotFound {
+                    loader: "Purpur".to_owned(),
+                    version: ver.to_string(),
+                    build: pinned.clone(),
+                });
+            }
+            download_purpur_fetch_build(ver, pinned)
+        }
+        BuildSelection::Latest => download_purpur_fetch_build(ver, &version.builds.latest),
+        BuildSelection::Recommended => {
+            // Each build is its own request, a version whose recent builds all failed isn't worth walking back further
+            for build in version.builds.all.iter().rev().take(PURPUR_RECOMMENDED_MAX_BUILDS) {
+                let build = download_purpur_fetch_build(ver, build)?;
+                if build.result == "SUCCESS" {
+                    return Ok(build);
+                }
+            }
+            Err(no_build())
+        }
+    }
+}
+
+pub fn download_purpur_fetch_versions() -> Result<PurpurProject, LibError> {
+    let text = cache_fetch_text(&format!("{PURPUR_API_URL}/v2/purpur"))?;
+    let project: PurpurProject = serde_json::from_str(&text)?;
+    Ok(project)
+}
+
+fn download_purpur_fetch_build(ver: &MinecraftVersion, build: &str) -> Result<PurpurBuild, LibError> {
+    let text = cache_fetch_text(&format!("{PURPUR_API_URL}/v2/purpur/{ver}/{build}"))?;
+    let build: PurpurBuild = serde_json::from_str(&text)?;
+    Ok(build)
+}
+
+//
+// Pufferfish Server
+//
+
+// Pufferfish is built on Jenkins, one job per Minecraft minor version (Pufferfish-1.20, Pufferfish-1.21, ...).
+#[derive(Debug, Deserialize)]
+pub struct JenkinsJobs {
+    pub jobs: Vec<JenkinsJobName>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JenkinsJobName {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JenkinsJob {
+    pub builds: Vec<JenkinsBuild>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct JenkinsBuild {
+    pub number: u32,
+    pub result: Option<String>,
+    pub artifacts: Vec<JenkinsArtifact>,
+    // Jenkins fingerprints artifacts by their MD5
+    #[serde(default)]
+    pub fingerprint: Vec<JenkinsFingerprint>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct JenkinsArtifact {
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    #[serde(rename = "relativePath")]
+    pub relative_path: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct JenkinsFingerprint {
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    pub hash: String,
+}
+
+// The tree query is percent-encoded, the brackets aren't valid in a URI as-is.
+const JENKINS_BUILDS_TREE: &str = "builds%5Bnumber,result,artifacts%5BfileName,relativePath%5D,fingerprint%5BfileName,hash%5D%5D";
+
+// The MD5 Jenkins fingerprinted the artifact with, a build without one is downloaded unverified with a warning.
+fn download_jenkins_artifact_checksum(software: &str, build: &JenkinsBuild, artifact: &JenkinsArtifact, reporter: &dyn ProgressReporter) -> Option<Checksum> {
+    match build.fingerprint.iter().find(|fingerprint| fingerprint.file_name == artifact.file_name) {
+        Some(fingerprint) => Some(Checksum::Md5(fingerprint.hash.clone())),
+        None => {
+            reporter.report(ProgressEvent::Warning { message: format!("{software} build {} has no fingerprint for {}, it can't be verified", build.number, artifact.file_name) });
+            None
+        }
+    }
+}
+
+pub fn download_pufferfish_server(ver: &MinecraftVersion, path: String, build: &BuildSelection, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let job = download_pufferfish_job_name(ver).ok_or_else(|| LibError::NoBuild {
+        loader: "Pufferfish".to_owned(),
+        version: ver.to_string(),
+    })?;
+    let (build, artifact) = meta_resolve_pufferfish_build(ver, build)?;
+    progress_phase(reporter, format!("Selected Pufferfish build {} ({})", build.number, artifact.file_name));
+
+    let url = format!("{PUFFERFISH_CI_URL}/job/{job}/{}/artifact/{}", build.number, artifact.relative_path);
+    let checksum = download_jenkins_artifact_checksum("Pufferfish", &build, &artifact, reporter);
+    download_file(&url, &Path::new(&path).join("server.jar"), checksum.as_ref(), reporter)?;
+
+    instance_write(Path::new(&path), &Instance::new(Modloaders::Pufferfish, ver.clone(), Some(build.number.to_string())))
+}
+
+// A job keeps building the newest patch release of its line, so builds are matched on the version in the jar name.
+pub fn meta_resolve_pufferfish_build(ver: &MinecraftVersion, selection: &BuildSelection) -> Result<(JenkinsBuild, JenkinsArtifact), LibError> {
+    let no_build = || LibError::NoBuild {
+        loader: "Pufferfish".to_owned(),
+        version: ver.to_string(),
+    };
+    let job = download_pufferfish_job_name(ver).ok_or_else(no_build)?;
+    let mut builds: Vec<(JenkinsBuild, JenkinsArtifact)> = download_pufferfish_fetch_builds(&job)?
+        .into_iter()
+        .filter_map(|build| {
+            let artifact = download_pufferfish_server_artifact(&build, ver)?;
+            Some((build, artifact))
+        })
+        .collect();
+    builds.sort_by_key(|(build, _)| build.number);
+
+    match selection {
+        BuildSelection::Pinned(pinned) => builds
+            .into_iter()
+            .find(|(build, _)| build.number.to_string() == *pinned)
+            .ok_or_else(|| LibError::BuildNotFound {
+                loader: "Pufferfish".to_owned(),
+                version: ver.to_string(),
+                build: pinned.clone(),
+            }),
+        BuildSelection::Recommended | BuildSelection::Latest => builds
+            .into_iter()
+            .rev()
+            .find(|(build, _)| build.result.as_deref() == Some("SUCCESS"))
+            .ok_or_else(no_build),
+    }
+}
+
+// Every Minecraft version some Pufferfish job has produced a server jar for.
+pub fn download_pufferfish_fetch_version_ids() -> Result<Vec<String>, LibError> {
+    let text = cache_fetch_text(&format!("{PUFFERFISH_CI_URL}/api/json?tree=jobs%5Bname%5D"))?;
+    let jobs: JenkinsJobs = serde_json::from_str(&text)?;
+
+    let mut ids = Vec::new();
+    for job in jobs.jobs.iter().filter(|job| job.name.starts_with("Pufferfish-1.")) {
+        for build in download_pufferfish_fetch_builds(&job.name)? {
+            for artifact in &build.artifacts {
+                if let Some(id) = download_pufferfish_artifact_version(&artifact.file_name)
+                    && !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+    Ok(ids)
+}
+
+fn download_pufferfish_job_name(ver: &MinecraftVersion) -> Option<String> {
+    match ver.numbers() {
+        [1, minor, ..] if ver.is_release() => Some(format!("Pufferfish-1.{minor}")),
+        _ => None,
+    }
+}
+
+fn download_pufferfish_fetch_builds(job: &str) -> Result<Vec<JenkinsBuild>, LibError> {
+    let text = cache_fetch_text(&format!("{PUFFERFISH_CI_URL}/job/{job}/api/json?tree={JENKINS_BUILDS_TREE}"))?;
+    let job: JenkinsJob = serde_json::from_str(&text)?;
+    Ok(job.builds)
+}
+
+// e.g. pufferfish-paperclip-1.20.4-R0.1-SNAPSHOT-reobf.jar
+fn download_pufferfish_artifact_version(file_name: &str) -> Option<String> {
+    let rest = file_name.strip_prefix("pufferfish-paperclip-")?;
+    let (version, _) = rest.split_once('-')?;
+    Some(version.to_owned())
+}
+
+fn download_pufferfish_server_artifact(build: &JenkinsBuild, ver: &MinecraftVersion) -> Option<JenkinsArtifact> {
+    build.artifacts
+        .iter()
+        .filter(|artifact| artifact.file_name.ends_with(".jar"))
+        .find(|artifact| download_pufferfish_artifact_version(&artifact.file_name).as_deref() == Some(ver.id()))
+        .cloned()
+}
+
+//
+// Proxy Server
+//
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProxySoftware {
+    Velocity,
+    Waterfall,
+    BungeeCord,
+}
+
+impl fmt::Display for ProxySoftware {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProxySoftware::Velocity => write!(f, "Velocity"),
+            ProxySoftware::Waterfall => write!(f, "Waterfall"),
+            ProxySoftware::BungeeCord => write!(f, "BungeeCord"),
+        }
+    }
+}
+
+// Proxies are saved as proxy.jar, so they can't be mistaken for a backend server.
+pub const PROXY_JAR_NAME: &str = "proxy.jar";
+
+pub fn download_paper_proxy_server(software: ProxySoftware, ver: &ProxyVersion, path: String, build: &BuildSelection, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let project_name = download_paper_proxy_project_name(software);
+    let build = meta_resolve_paper_proxy_build(software, ver, build)?;
+    progress_phase(reporter, format!("Selected {software} {ver} build {} ({})", build.build, build.channel));
+
+    let application = &build.downloads.application;
+    let url = format!("{PAPER_API_URL}/v2/projects/{project_name}/versions/{ver}/builds/{}/downloads/{}", build.build, application.name);
+    let checksum = Checksum::Sha256(application.sha256.clone());
+    download_file(&url, &Path::new(&path).join(PROXY_JAR_NAME), Some(&checksum), reporter)?;
+
+    instance_write(Path::new(&path), &ProxyInstance {
+        proxy: software,
+        version: ver.clone(),
+        build: Some(build.build.to_string()),
+    })
+}
+
+// Recommended prefers builds on the default channel, latest takes the newest build of the version.
+pub fn meta_resolve_paper_proxy_build(software: ProxySoftware, ver: &ProxyVersion, selection: &BuildSelection) -> Result<PaperBuild, LibError> {
+    let project_name = download_paper_proxy_project_name(software);
+    let no_build = || LibError::NoBuild {
+        loader: software.to_string(),
+        version: ver.to_string(),
+    };
+    let project = download_paper_fetch_project(project_name)?;
+    if !project.versions.iter().any(|v| v == ver.id()) {
+        return Err(no_build());
+    }
+
+    let text = cache_fetch_text(&format!("{PAPER_API_URL}/v2/projects/{project_name}/versions/{ver}/builds"))?;
+    let mut builds = serde_json::from_str::<PaperVersionBuilds>(&text)?.builds;
+    builds.sort_by_key(|build| build.build);
+
+    match selection {
+        BuildSelection::Pinned(pinned) => builds
+            .into_iter()
+            .find(|build| build.build.to_string() == *pinned)
+            .ok_or_else(|| LibError::BuildNotFound {
+                loader: software.to_string(),
+                version: ver.to_string(),
+                build: pinned.clone(),
+            }),
+        BuildSelection::Recommended => {
+            let recommended = builds.iter().rposition(|build| build.channel == "default");
+            let index = recommended.or(builds.len().checked_sub(1)).ok_or_else(no_build)?;
+            Ok(builds.swap_remove(index))
+        }
+        BuildSelection::Latest => builds.pop().ok_or_else(no_build),
+    }
+}
+
+fn download_paper_proxy_project_name(software: ProxySoftware) -> &'static str {
+    match software {
+        ProxySoftware::Velocity => "velocity",
+        ProxySoftware::Waterfall => "waterfall",
+        ProxySoftware::BungeeCord => "bungeecord",
+    }
+}
+
+// BungeeCord has no release versions, every successful Jenkins build is one.
+pub fn download_bungeecord_server(ver: &ProxyVersion, path: String, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let (build, artifact) = meta_resolve_bungeecord_build(ver)?;
+    progress_phase(reporter, format!("Selected BungeeCord build {}", build.number));
+
+    let url = format!("{BUNGEECORD_CI_URL}/job/BungeeCord/{}/artifact/{}", build.number, artifact.relative_path);
+    let checksum = download_jenkins_artifact_checksum("BungeeCord", &build, &artifact, reporter);
+    download_file(&url, &Path::new(&path).join(PROXY_JAR_NAME), checksum.as_ref(), reporter)?;
+
+    instance_write(Path::new(&path), &ProxyInstance {
+        proxy: ProxySoftware::BungeeCord,
+        version: ver.clone(),
+        build: Some(build.number.to_string()),
+    })
+}
+
+pub fn meta_resolve_bungeecord_build(ver: &ProxyVersion) -> Result<(JenkinsBuild, JenkinsArtifact), LibError> {
+    download_bungeecord_fetch_builds()?
+        .into_iter()
+        .filter(|build| build.number.to_string() == ver.id())
+        .find_map(|build| {
+            let artifact = download_bungeecord_artifact(&build)?;
+            Some((build, artifact))
+        })
+        .ok_or_else(|| LibError::NoBuild {
+            loader: "BungeeCord".to_owned(),
+            version: ver.to_string(),
+        })
+}
+
+fn download_bungeecord_fetch_builds() -> Result<Vec<JenkinsBuild>, LibError> {
+    let text = cache_fetch_text(&format!("{BUNGEECORD_CI_URL}/job/BungeeCord/api/json?tree={JENKINS_BUILDS_TREE}"))?;
+    let job: JenkinsJob = serde_json::from_str(&text)?;
+    Ok(job.builds)
+}
+
+fn download_bungeecord_artifact(build: &JenkinsBuild) -> Option<JenkinsArtifact> {
+    build.artifacts.iter().find(|artifact| artifact.file_name == "BungeeCord.jar").cloned()
+}
+
+//
+// Bedrock Server
+//
+
+#[derive(Debug, Deserialize)]
+pub struct BedrockLinksResponse {
+    pub result: BedrockLinks,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BedrockLinks {
+    pub links: Vec<BedrockLink>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BedrockLink {
+    #[serde(rename = "downloadType")]
+    pub download_type: String,
+    #[serde(rename = "downloadUrl")]
+    pub download_url: String,
+}
+
+pub const BEDROCK_LATEST_ALIAS: &str = "latest";
+pub const BEDROCK_PREVIEW_ALIAS: &str = "preview";
+
+// Files players and admins change, an upgrade never overwrites them.
+pub const BEDROCK_PRESERVED_FILES: [&str; 4] = ["worlds", "server.properties", "permissions.json", "allowlist.json"];
+
+// Installs into an empty directory or upgrades an existing Bedrock instance in place.
+pub fn download_bedrock_server(ver: &BedrockVersion, path: String, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    if !cfg!(target_os = "linux") {
+        return Err(LibError::Misc("Only the Linux build of Bedrock Dedicated Server is supported".to_owned()));
+    }
+    let url = ver.download_url();
+    let zip_path = Path::new(&path).join("bedrock-server.zip");
+    download_file(&url, &zip_path, None, reporter)?;
+
+    reporter.report(ProgressEvent::Extracting { file: zip_path.to_string_lossy().to_string() });
+    download_bedrock_unpack_zip(&zip_path, Path::new(&path))?;
+    fs::remove_file(&zip_path)?;
+
+    instance_write(Path::new(&path), &BedrockInstance {
+        bedrock_version: ver.clone(),
+    })
+}
+
+// Moves a Bedrock instance to the newest release, returns false if it already is up to date.
+pub fn download_bedrock_update_server(path: String, reporter: &dyn ProgressReporter) -> Result<bool, LibError> {
+    let instance = match instance_read_kind(Path::new(&path))? {
+        InstanceKind::Bedrock(instance) => instance,
+        _ => return Err(LibError::Misc(format!("{path} is not a Bedrock instance"))),
+    };
+    let latest = meta_resolve_bedrock_version(BEDROCK_LATEST_ALIAS)?;
+    if latest <= instance.bedrock_version {
+        progress_phase(reporter, format!("Already on the newest version ({}).", instance.bedrock_version));
+        return Ok(false);
+    }
+    progress_phase(reporter, format!("Updating from {} to {latest}...", instance.bedrock_version));
+    download_bedrock_server(&latest, path, reporter)?;
+    Ok(true)
+}
+
+fn download_bedrock_unpack_zip(zip_path: &Path, save_path: &Path) -> Result<(), LibError> {
+    let archive_error = |e: zip::result::ZipError| LibError::Archive {
+        file: zip_path.to_string_lossy().to_string(),
+        reason: e.to_string(),
+    };
+    let file = File::open(zip_path)?;
+    let mut archive = ZipArchive::new(file).map_err(archive_error)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(archive_error)?;
+        let Some(path) = entry.enclosed_name() else {
+            continue;
+        };
+
+        let top_level = path.components().next().map(|c| c.as_os_str().to_string_lossy().to_string());
+        if let Some(top_level) = top_level
+            && BEDROCK_PRESERVED_FILES.contains(&top_level.as_str())
+            && save_path.join(&top_level).exists() {
+            continue;
+        }
+
+        let out_path = save_path.join(&path);
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path)?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut outfile = File::create(&out_path)?;
+        std::io::copy(&mut entry, &mut outfile)?;
+
+        // bedrock_server has to stay executable
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&out_path, fs::Permissions::from_mode(mode))?;
+        }
+    }
+    Ok(())
+}
+
+// The server runs natively, it ships its own libraries next to the binary.
+pub fn bedrock_launch_command(path: &Path) -> Result<LaunchCommand, LibError> {
+    match instance_read_kind(path)? {
+        InstanceKind::Bedrock(_) => Ok(LaunchCommand {
+            program: path.join("bedrock_server").to_string_lossy().to_string(),
+            args: Vec::new(),
+            working_dir: path.to_path_buf(),
+            env: vec![("LD_LIBRARY_PATH".to_owned(), ".".to_owned())],
+        }),
+        _ => Err(LibError::Misc(format!("{} is not a Bedrock instance", path.display()))),
+    }
+}
+
+//
+// Sponge Server
+//
+
+#[derive(Clone, Debug, Default)]
+pub struct SpongeOptions {
+    pub build: BuildSelection,
+    // Sponge API major version, e.g. 8 or 11. Any API the Minecraft version has builds for if unset.
+    pub api: Option<String>,
+}
+
+pub fn download_sponge_vanilla_server(ver: &MinecraftVersion, path: String, options: &SpongeOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let (sponge_version, build) = meta_resolve_sponge_build(ver, false, options)?;
+    progress_phase(reporter, format!("Selected SpongeVanilla {sponge_version}"));
+    download_sponge_universal_jar(&build, &Path::new(&path).join("server.jar"), reporter)?;
+
+    instance_write(Path::new(&path), &Instance::new(Modloaders::SpongeVanilla, ver.clone(), Some(sponge_version)))
+}
+
+// SpongeForge is a Forge mod, it goes into mods/ of a Forge install of the version it was built against.
+pub fn download_sponge_forge_server(ver: &MinecraftVersion, path: String, options: &SpongeOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let (sponge_version, build) = meta_resolve_sponge_build(ver, true, options)?;
+    let forge_version = build.tags.get("forge").cloned().ok_or_else(|| LibError::Misc(format!("SpongeForge {sponge_version} does not name its Forge version")))?;
+    progress_phase(reporter, format!("Selected SpongeForge {sponge_version} on Forge {forge_version}"));
+
+    download_forge_server(ver, path.clone(), &BuildSelection::Pinned(forge_version), reporter)?;
+
+    let mods_dir = Path::new(&path).join("mods");
+    fs::create_dir_all(&mods_dir)?;
+    download_sponge_universal_jar(&build, &mods_dir.join(format!("spongeforge-{sponge_version}.jar")), reporter)?;
+
+    instance_write(Path::new(&path), &Instance::new(Modloaders::SpongeForge, ver.clone(), Some(sponge_version)))
+}
+
+fn download_sponge_universal_jar(build: &SpongeVersion, dest: &Path, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let asset = build.assets
+        .iter()
+        .find(|asset| asset.classifier == "universal" && asset.extension == "jar")
+        .ok_or_else(|| LibError::Misc("Sponge build has no universal jar".to_owned()))?;
+    let checksum = asset.sha1.clone().map(Checksum::Sha1);
+    download_file(&asset.download_url, dest, checksum.as_ref(), reporter)
+}
+
+//
+// Hybrid Server
+//
+
+// Mohist (Forge) and Youer (NeoForge) are published through MohistMC's API, Arclight through GitHub releases.
+#[derive(Debug, Deserialize)]
+pub struct MohistProject {
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MohistBuilds {
+    pub builds: Vec<MohistBuild>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct MohistBuild {
+    pub number: u32,
+    #[serde(rename = "forgeVersion")]
+    pub forge_version: Option<String>,
+    #[serde(rename = "fileMd5")]
+    pub file_md5: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GithubRelease {
+    pub tag_name: String,
+    pub prerelease: bool,
+    pub assets: Vec<GithubAsset>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GithubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    // e.g. sha256:7f3a..., GitHub only computes it for newer uploads
+    pub digest: Option<String>,
+}
+
+fn download_mohist_project_name(loader: Modloaders) -> &'static str {
+    if loader == Modloaders::Youer { "youer" } else { "mohist" }
+}
+
+pub fn download_mohist_server(loader: Modloaders, ver: &MinecraftVersion, path: String, build: &BuildSelection, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let project = download_mohist_project_name(loader);
+    let build = meta_resolve_mohist_build(loader, ver, build)?;
+    match &build.forge_version {
+        Some(forge_version) => progress_phase(reporter, format!("Selected {loader:?} build {} (Forge {forge_version})", build.number)),
+        None => progress_phase(reporter, format!("Selected {loader:?} build {}", build.number)),
+    }
+
+    let url = format!("{MOHIST_API_URL}/api/v2/projects/{project}/{ver}/builds/{}/download", build.number);
+    let checksum = build.file_md5.clone().map(Checksum::Md5);
+    download_file(&url, &Path::new(&path).join("server.jar"), checksum.as_ref(), reporter)?;
+
+    download_hybrid_finish(loader, ver, &path, build.number.to_string())
+}
+
+// Neither API marks builds as stable, recommended and latest both take the newest build.
+pub fn meta_resolve_mohist_build(loader: Modloaders, ver: &MinecraftVersion, selection: &BuildSelection) -> Result<MohistBuild, LibError> {
+    let project = download_mohist_project_name(loader);
+    let no_build = || LibError::NoBuild {
+        loader: format!("{loader:?}"),
+        version: ver.to_string(),
+    };
+    if !download_mohist_fetch_versions(loader)?.versions.iter().any(|v| v == ver.id()) {
+        return Err(no_build());
+    }
+
+    let text = cache_fetch_text(&format!("{MOHIST_API_URL}/api/v2/projects/{project}/{ver}/builds"))?;
+    let mut builds = serde_json::from_str::<MohistBuilds>(&text)?.builds;
+    builds.sort_by_key(|build| build.number);
+
+    match selection {
+        BuildSelection::Pinned(pinned) => builds
+            .into_iter()
+            .find(|build| build.number.to_string() == *pinned)
+            .ok_or_else(|| LibError::BuildNotFound {
+                loader: format!("{loader:?}"),
+                version: ver.to_string(),
+                build: pinned.clone(),
+            }),
+        BuildSelection::Recommended | BuildSelection::Latest => builds.pop().ok_or_else(no_build),
+    }
+}
+
+pub fn download_mohist_fetch_versions(loader: Modloaders) -> Result<MohistProject, LibError> {
+    let project = download_mohist_project_name(loader);
+    let text = cache_fetch_text(&format!("{MOHIST_API_URL}/api/v2/projects/{project}"))?;
+    let project: MohistProject = serde_json::from_str(&text)?;
+    Ok(project)
+}
+
+pub fn download_arclight_server(ver: &MinecraftVersion, path: String, build: &BuildSelection, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    let (release, asset) = meta_resolve_arclight_build(ver, build)?;
+    progress_phase(reporter, format!("Selected Arclight {} ({})", release.tag_name, asset.name));
+
+    let checksum = asset.digest
+        .as_deref()
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+        .map(|hash| Checksum::Sha256(hash.to_owned()));
+    download_file(&asset.browser_download_url, &Path::new(&path).join("server.jar"), checksum.as_ref(), reporter)?;
+
+    download_hybrid_finish(Modloaders::Arclight, ver, &path, release.tag_name)
+}
+
+// Releases carry one jar per loader and Minecraft version, e.g. arclight-forge-1.20.1-1.0.5.jar.
+// Recommended skips pre-releases, pinned builds are release tags.
+pub fn meta_resolve_arclight_build(ver: &MinecraftVersion, selection: &BuildSelection) -> Result<(GithubRelease, GithubAsset), LibError> {
+    let releases = download_arclight_fetch_releases()?;
+
+    let mut candidates = releases.into_iter().filter_map(|release| {
+        let asset = download_arclight_server_asset(&release, ver)?;
+        Some((release, asset))
+    });
+    let found = match selection {
+        BuildSelection::Pinned(pinned) => {
+            return candidates
+                .find(|(release, _)| release.tag_name == *pinned)
+                .ok_or_else(|| LibError::BuildNotFound {
+                    loader: "Arclight".to_owned(),
+                    version: ver.to_string(),
+                    build: pinned.clone(),
+                });
+        }
+        BuildSelection::Recommended => candidates.find(|(release, _)| !release.prerelease),
+        BuildSelection::Latest => candidates.next(),
+    };
+    found.ok_or_else(|| LibError::NoBuild {
+        loader: "Arclight".to_owned(),
+        version: ver.to_string(),
+    })
+}
+
+// Every Minecraft version an Arclight Forge jar has been released for.
+pub fn download_arclight_fetch_version_ids() -> Result<Vec<String>, LibError> {
+    let releases = download_arclight_fetch_releases()?;
+    let mut ids = Vec::new();
+    for asset in releases.iter().flat_map(|release| release.assets.iter()) {
+        if let Some(rest) = asset.name.strip_prefix("arclight-forge-")
+            && let Some((id, _)) = rest.split_once('-')
+            && !ids.iter().any(|known| known == id) {
+            ids.push(id.to_owned());
+        }
+    }
+    Ok(ids)
+}
+
+// GitHub pages releases newest first, older Minecraft versions are only on the later pages.
+pub fn download_arclight_fetch_releases() -> Result<Vec<GithubRelease>, LibError> {
+    let mut releases = Vec::new();
+    for page in cache_fetch_pages(&format!("{GITHUB_API_URL}/repos/IzzelAliz/Arclight/releases?per_page=100"))? {
+        releases.extend(serde_json::from_str::<Vec<GithubRelease>>(&page)?);
+    }
+    Ok(releases)
+}
+
+fn download_arclight_server_asset(release: &GithubRelease, ver: &MinecraftVersion) -> Option<GithubAsset> {
+    let prefix = format!("arclight-forge-{ver}-");
+    release.assets
+        .iter()
+        .find(|asset| asset.name.starts_with(&prefix) && asset.name.ends_with(".jar"))
+        .cloned()
+}
+
+// Hybrids install their libraries on first start, the instance only needs both content folders.
+fn download_hybrid_finish(loader: Modloaders, ver: &MinecraftVersion, path: &str, build: String) -> Result<(), LibError> {
+    let instance = Instance::new(loader, ver.clone(), Some(build));
+    for kind in instance.content_kinds() {
+        fs::create_dir_all(Path::new(path).join(kind.dir_name()))?;
+    }
+    instance_write(Path::new(path), &instance)
+}
+
+//
+// Server Installers
+//
+
+// What an instance runs: a Java server with its loader, a proxy or a Bedrock Dedicated Server.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ServerKind {
+    Java(Modloaders),
+    Proxy(ProxySoftware),
+    Bedrock,
+}
+
+impl fmt::Display for ServerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerKind::Java(loader) => write!(f, "{loader:?}"),
+            ServerKind::Proxy(software) => write!(f, "{software}"),
+            ServerKind::Bedrock => write!(f, "Bedrock"),
+        }
+    }
+}
+
+// Loader, proxy and Bedrock names don't overlap, so a single name picks any of them, e.g. `paper` or `velocity`.
+impl FromStr for ServerKind {
+    type Err = LibError;
+
+    fn from_str(s: &str) -> Result<ServerKind, LibError> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("bedrock") {
+            return Ok(ServerKind::Bedrock);
+        }
+        if let Ok(loader) = <Modloaders as ValueEnum>::from_str(s, true) {
+            return Ok(ServerKind::Java(loader));
+        }
+        match <ProxySoftware as ValueEnum>::from_str(s, true) {
+            Ok(software) => Ok(ServerKind::Proxy(software)),
+            Err(_) => Err(LibError::Misc(format!("unknown server software {s}"))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ServerKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ServerKind, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(de::Error::custom)
+    }
+}
+
+// A version of whatever a ServerKind runs, installers only accept their own kind of version.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameVersion {
+    Java(MinecraftVersion),
+    Proxy(ProxyVersion),
+    Bedrock(BedrockVersion),
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameVersion::Java(ver) => write!(f, "{ver}"),
+            GameVersion::Proxy(ver) => write!(f, "{ver}"),
+            GameVersion::Bedrock(ver) => write!(f, "{ver}"),
+        }
+    }
+}
+
+impl GameVersion {
+    pub fn minecraft(&self) -> Result<&MinecraftVersion, LibError> {
+        match self {
+            GameVersion::Java(ver) => Ok(ver),
+            ver => Err(LibError::Ver(format!("{ver} is not a Minecraft version"))),
+        }
+    }
+
+    pub fn proxy(&self) -> Result<&ProxyVersion, LibError> {
+        match self {
+            GameVersion::Proxy(ver) => Ok(ver),
+            ver => Err(LibError::Ver(format!("{ver} is not a proxy version"))),
+        }
+    }
+
+    pub fn bedrock(&self) -> Result<&BedrockVersion, LibError> {
+        match self {
+            GameVersion::Bedrock(ver) => Ok(ver),
+            ver => Err(LibError::Ver(format!("{ver} is not a Bedrock version"))),
+        }
+    }
+}
+
+// Everything an install can be tuned with, each installer only reads the options that apply to it.
+#[derive(Clone, Debug, Default)]
+pub struct InstallOptions {
+    pub build: BuildSelection,
+    // Fabric/Quilt installer version
+    pub installer: BuildSelection,
+    pub channel: PaperChannel,
+    pub server_launcher: bool,
+    // Sponge API major version
+    pub sponge_api: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LaunchCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub working_dir: PathBuf,
+    pub env: Vec<(String, String)>,
+}
+
+pub trait ServerInstaller: Send + Sync {
+    fn kind(&self) -> ServerKind;
+
+    fn name(&self) -> &'static str;
+
+    // Resolves aliases like latest.release, latest or preview to the version `install` takes.
+    fn resolve_version(&self, ver: &str) -> Result<GameVersion, LibError> {
+        match self.kind() {
+            ServerKind::Java(_) => Ok(GameVersion::Java(meta_resolve_minecraft_version(ver)?)),
+            ServerKind::Proxy(software) => Ok(GameVersion::Proxy(meta_resolve_proxy_version(software, ver)?)),
+            ServerKind::Bedrock => Ok(GameVersion::Bedrock(meta_resolve_bedrock_version(ver)?)),
+        }
+    }
+
+    // Versions the software has builds for, newest first.
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError>;
+
+    // The runtime the server needs, None for software that doesn't run on Java.
+    fn java_version(&self, ver: &GameVersion) -> Result<Option<JavaVersion>, LibError> {
+        match self.kind() {
+            ServerKind::Java(loader) => Ok(Some(java_resolve_version(ver.minecraft()?, loader)?)),
+            ServerKind::Proxy(software) => Ok(Some(java_resolve_proxy_version(software, ver.proxy()?))),
+            ServerKind::Bedrock => Ok(None),
+        }
+    }
+
+    // The build `install` would pick, None for software without separate builds.
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError>;
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError>;
+
+    // Moves the instance at `path` to the newest build, returns false if it already is up to date.
+    fn update(&self, path: &Path, _options: &InstallOptions, _reporter: &dyn ProgressReporter) -> Result<bool, LibError> {
+        Err(LibError::Misc(format!("{} instances at {} can't be updated in place", self.name(), path.display())))
+    }
+
+    // How to start the server installed at `path`, read from its instance file.
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError>;
+}
+
+static SERVER_INSTALLERS: &[&dyn ServerInstaller] = &[
+    &VanillaInstaller,
+    &ForgeInstaller,
+    &NeoForgeInstaller,
+    &FabricInstaller,
+    &PaperInstaller { folia: false },
+    &PaperInstaller { folia: true },
+    &PurpurInstaller,
+    &PufferfishInstaller,
+    &QuiltInstaller,
+    &SpongeInstaller { forge: false },
+    &SpongeInstaller { forge: true },
+    &MohistInstaller { youer: false },
+    &ArclightInstaller,
+    &MohistInstaller { youer: true },
+    &PaperProxyInstaller { software: ProxySoftware::Velocity },
+    &PaperProxyInstaller { software: ProxySoftware::Waterfall },
+    &BungeeCordInstaller,
+    &BedrockInstaller,
+];
+
+pub fn installer_registry() -> &'static [&'static dyn ServerInstaller] {
+    SERVER_INSTALLERS
+}
+
+pub fn installer_for(kind: ServerKind) -> Result<&'static dyn ServerInstaller, LibError> {
+    SERVER_INSTALLERS
+        .iter()
+        .copied()
+        .find(|installer| installer.kind() == kind)
+        .ok_or_else(|| LibError::Misc(format!("No installer registered for {kind}")))
+}
+
+pub struct VanillaInstaller;
+
+impl ServerInstaller for VanillaInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(Modloaders::Vanilla)
+    }
+
+    fn name(&self) -> &'static str {
+        "Vanilla"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        Ok(meta_fetch_game_versions_of_kind(&VersionKind::ALL)?.into_iter().map(GameVersion::Java).collect())
+    }
+
+    fn resolve_build(&self, _ver: &GameVersion, _options: &InstallOptions) -> Result<Option<String>, LibError> {
+        Ok(None)
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, _options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        download_vanilla_server(ver, path.display().to_string(), reporter)?;
+        installer_write_instance(path, Modloaders::Vanilla, ver, None)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_jar_launch_command(path, Modloaders::Vanilla, "server.jar")
+    }
+}
+
+pub struct ForgeInstaller;
+
+impl ServerInstaller for ForgeInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(Modloaders::Forge)
+    }
+
+    fn name(&self) -> &'static str {
+        "Forge"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let meta = meta_fetch_forge_metadata()?;
+        installer_game_versions_where(|ver| meta.minecraft_versions.contains_key(ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        Ok(Some(meta_resolve_forge_build(ver, &options.build)?))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        let build = meta_resolve_forge_build(ver, &options.build)?;
+        download_forge_server(ver, path.display().to_string(), &BuildSelection::Pinned(build.clone()), reporter)?;
+        installer_write_instance(path, Modloaders::Forge, ver, Some(build))
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        let instance = installer_read_instance(path, Modloaders::Forge)?;
+        let build = instance.build.clone().unwrap_or_default();
+        installer_forge_launch_command(path, &instance, &build)
+    }
+}
+
+pub struct NeoForgeInstaller;
+
+impl ServerInstaller for NeoForgeInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(Modloaders::NeoForge)
+    }
+
+    fn name(&self) -> &'static str {
+        "NeoForge"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let meta = meta_fetch_neoforge_versions()?;
+        installer_game_versions_where(|ver| match meta_neoforge_version_prefix(ver) {
+            Some(prefix) => meta.versions.iter().any(|build| build.starts_with(&prefix)),
+            None => false,
+        })
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        Ok(Some(meta_resolve_neoforge_build(ver, &options.build)?))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        let build = meta_resolve_neoforge_build(ver, &options.build)?;
+        download_neoforge_server(path.display().to_string(), ver, reporter, &BuildSelection::Pinned(build.clone()))?;
+        installer_write_instance(path, Modloaders::NeoForge, ver, Some(build))
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        let instance = installer_read_instance(path, Modloaders::NeoForge)?;
+        let build = instance.build.clone().unwrap_or_default();
+        installer_args_file_launch_command(path, &instance, &format!("libraries/net/neoforged/neoforge/{build}"))
+    }
+}
+
+pub struct FabricInstaller;
+
+impl ServerInstaller for FabricInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(Modloaders::Fabric)
+    }
+
+    fn name(&self) -> &'static str {
+        "Fabric"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let game_versions = meta_fetch_fabric_game_versions()?;
+        installer_game_versions_where(|ver| game_versions.iter().any(|game| game.version == ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        Ok(Some(meta_resolve_fabric_loader(ver, &options.build)?.version))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        let loader = meta_resolve_fabric_loader(ver, &options.build)?;
+        let fabric_options = FabricOptions {
+            loader: BuildSelection::Pinned(loader.version.clone()),
+            installer: options.installer.clone(),
+            server_launcher: options.server_launcher,
+        };
+        download_fabric_server(ver, path.display().to_string(), &fabric_options, reporter)?;
+        installer_write_instance(path, Modloaders::Fabric, ver, Some(loader.version))
+    }
+
+    // The installer writes fabric-server-launch.jar, the single-file launcher is saved as server.jar.
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        let jar = if path.join("fabric-server-launch.jar").exists() { "fabric-server-launch.jar" } else { "server.jar" };
+        installer_jar_launch_command(path, Modloaders::Fabric, jar)
+    }
+}
+
+pub struct QuiltInstaller;
+
+impl ServerInstaller for QuiltInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(Modloaders::Quilt)
+    }
+
+    fn name(&self) -> &'static str {
+        "Quilt"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let game_versions = meta_fetch_quilt_game_versions()?;
+        installer_game_versions_where(|ver| game_versions.iter().any(|game| game.version == ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        Ok(Some(meta_resolve_quilt_loader(ver, &options.build)?.version))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        let loader = meta_resolve_quilt_loader(ver, &options.build)?;
+        let quilt_options = QuiltOptions {
+            loader: BuildSelection::Pinned(loader.version.clone()),
+            installer: options.installer.clone(),
+        };
+        download_quilt_server(ver, path.display().to_string(), &quilt_options, reporter)?;
+        installer_write_instance(path, Modloaders::Quilt, ver, Some(loader.version))
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_jar_launch_command(path, Modloaders::Quilt, "quilt-server-launch.jar")
+    }
+}
+
+pub struct SpongeInstaller {
+    pub forge: bool,
+}
+
+impl SpongeInstaller {
+    fn loader(&self) -> Modloaders {
+        if self.forge { Modloaders::SpongeForge } else { Modloaders::SpongeVanilla }
+    }
+}
+
+impl ServerInstaller for SpongeInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(self.loader())
+    }
+
+    fn name(&self) -> &'static str {
+        if self.forge { "SpongeForge" } else { "SpongeVanilla" }
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let artifact = meta_fetch_sponge_artifact(self.forge)?;
+        let ids = artifact.tags.get("minecraft").cloned().unwrap_or_default();
+        installer_game_versions_where(|ver| ids.iter().any(|id| id == ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        let (sponge_version, _) = meta_resolve_sponge_build(ver, self.forge, &installer_sponge_options(options))?;
+        Ok(Some(sponge_version))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        let sponge_options = installer_sponge_options(options);
+        if self.forge {
+            download_sponge_forge_server(ver, path.display().to_string(), &sponge_options, reporter)
+        } else {
+            download_sponge_vanilla_server(ver, path.display().to_string(), &sponge_options, reporter)
+        }
+    }
+
+    // The instance records the Sponge version, the Forge build underneath is read back from the install.
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        if !self.forge {
+            return installer_jar_launch_command(path, self.loader(), "server.jar");
+        }
+        let instance = installer_read_instance(path, self.loader())?;
+        let forge_build = installer_installed_forge_build(path)?;
+        installer_forge_launch_command(path, &instance, &forge_build)
+    }
+}
+
+fn installer_sponge_options(options: &InstallOptions) -> SpongeOptions {
+    SpongeOptions {
+        build: options.build.clone(),
+        api: options.sponge_api.clone(),
+    }
+}
+
+// Mohist and Youer are published through the same API, Youer is the NeoForge counterpart.
+pub struct MohistInstaller {
+    pub youer: bool,
+}
+
+impl MohistInstaller {
+    fn loader(&self) -> Modloaders {
+        if self.youer { Modloaders::Youer } else { Modloaders::Mohist }
+    }
+}
+
+impl ServerInstaller for MohistInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(self.loader())
+    }
+
+    fn name(&self) -> &'static str {
+        if self.youer { "Youer" } else { "Mohist" }
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let ids = download_mohist_fetch_versions(self.loader())?.versions;
+        installer_game_versions_where(|ver| ids.iter().any(|id| id == ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        Ok(Some(meta_resolve_mohist_build(self.loader(), ver, &options.build)?.number.to_string()))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        download_mohist_server(self.loader(), ver, path.display().to_string(), &options.build, reporter)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_jar_launch_command(path, self.loader(), "server.jar")
+    }
+}
+
+pub struct ArclightInstaller;
+
+impl ServerInstaller for ArclightInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(Modloaders::Arclight)
+    }
+
+    fn name(&self) -> &'static str {
+        "Arclight"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let ids = download_arclight_fetch_version_ids()?;
+        installer_game_versions_where(|ver| ids.iter().any(|id| id == ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        Ok(Some(meta_resolve_arclight_build(ver, &options.build)?.0.tag_name))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        download_arclight_server(ver, path.display().to_string(), &options.build, reporter)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_jar_launch_command(path, Modloaders::Arclight, "server.jar")
+    }
+}
+
+pub struct PaperInstaller {
+    pub folia: bool,
+}
+
+impl PaperInstaller {
+    fn loader(&self) -> Modloaders {
+        if self.folia { Modloaders::Folia } else { Modloaders::Paper }
+    }
+}
+
+impl ServerInstaller for PaperInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(self.loader())
+    }
+
+    fn name(&self) -> &'static str {
+        if self.folia { "Folia" } else { "Paper" }
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let project = download_paper_fetch_versions(self.folia)?;
+        installer_game_versions_where(|ver| project.versions.iter().any(|id| id == ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        let build = meta_resolve_paper_build(ver, self.folia, &installer_paper_options(options))?;
+        Ok(Some(build.build.to_string()))
+    }
+
+    // Paper writes its own instance file, it is needed for updates as well.
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        download_paper_server(ver, path.display().to_string(), &installer_paper_options(options), reporter, self.folia)
+    }
+
+    fn update(&self, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<bool, LibError> {
+        download_paper_update_server(path.display().to_string(), options.channel, reporter)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_jar_launch_command(path, self.loader(), "server.jar")
+    }
+}
+
+pub struct PurpurInstaller;
+
+impl ServerInstaller for PurpurInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(Modloaders::Purpur)
+    }
+
+    fn name(&self) -> &'static str {
+        "Purpur"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let project = download_purpur_fetch_versions()?;
+        installer_game_versions_where(|ver| project.versions.iter().any(|id| id == ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        Ok(Some(meta_resolve_purpur_build(ver, &options.build)?.build))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        download_purpur_server(ver, path.display().to_string(), &options.build, reporter)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_jar_launch_command(path, Modloaders::Purpur, "server.jar")
+    }
+}
+
+pub struct PufferfishInstaller;
+
+impl ServerInstaller for PufferfishInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Java(Modloaders::Pufferfish)
+    }
+
+    fn name(&self) -> &'static str {
+        "Pufferfish"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        let ids = download_pufferfish_fetch_version_ids()?;
+        installer_game_versions_where(|ver| ids.iter().any(|id| id == ver.id()))
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.minecraft()?;
+        let (build, _) = meta_resolve_pufferfish_build(ver, &options.build)?;
+        Ok(Some(build.number.to_string()))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.minecraft()?;
+        download_pufferfish_server(ver, path.display().to_string(), &options.build, reporter)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_jar_launch_command(path, Modloaders::Pufferfish, "server.jar")
+    }
+}
+
+// Velocity and Waterfall are published through the PaperMC API.
+pub struct PaperProxyInstaller {
+    pub software: ProxySoftware,
+}
+
+impl ServerInstaller for PaperProxyInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Proxy(self.software)
+    }
+
+    fn name(&self) -> &'static str {
+        match self.software {
+            ProxySoftware::Velocity => "Velocity",
+            ProxySoftware::Waterfall => "Waterfall",
+            ProxySoftware::BungeeCord => "BungeeCord",
+        }
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        Ok(meta_fetch_proxy_versions(self.software)?.into_iter().map(GameVersion::Proxy).collect())
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.proxy()?;
+        Ok(Some(meta_resolve_paper_proxy_build(self.software, ver, &options.build)?.build.to_string()))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.proxy()?;
+        download_paper_proxy_server(self.software, ver, path.display().to_string(), &options.build, reporter)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_proxy_launch_command(path, self.software)
+    }
+}
+
+pub struct BungeeCordInstaller;
+
+impl BungeeCordInstaller {
+    // The version already is the Jenkins build, a separate build selection would contradict it.
+    fn check_build(options: &InstallOptions) -> Result<(), LibError> {
+        match options.build {
+            BuildSelection::Recommended => Ok(()),
+            ref build => Err(LibError::Misc(format!("BungeeCord builds are picked through the version, build {build} can't be selected"))),
+        }
+    }
+}
+
+impl ServerInstaller for BungeeCordInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Proxy(ProxySoftware::BungeeCord)
+    }
+
+    fn name(&self) -> &'static str {
+        "BungeeCord"
+    }
+
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        Ok(meta_fetch_proxy_versions(ProxySoftware::BungeeCord)?.into_iter().map(GameVersion::Proxy).collect())
+    }
+
+    fn resolve_build(&self, ver: &GameVersion, options: &InstallOptions) -> Result<Option<String>, LibError> {
+        let ver = ver.proxy()?;
+        BungeeCordInstaller::check_build(options)?;
+        Ok(Some(meta_resolve_bungeecord_build(ver)?.0.number.to_string()))
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        let ver = ver.proxy()?;
+        BungeeCordInstaller::check_build(options)?;
+        download_bungeecord_server(ver, path.display().to_string(), reporter)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        installer_proxy_launch_command(path, ProxySoftware::BungeeCord)
+    }
+}
+
+pub struct BedrockInstaller;
+
+impl ServerInstaller for BedrockInstaller {
+    fn kind(&self) -> ServerKind {
+        ServerKind::Bedrock
+    }
+
+    fn name(&self) -> &'static str {
+        "Bedrock"
+    }
+
+    // Mojang only announces the current release and preview.
+    fn list_versions(&self) -> Result<Vec<GameVersion>, LibError> {
+        [BEDROCK_LATEST_ALIAS, BEDROCK_PREVIEW_ALIAS]
+            .iter()
+            .map(|alias| Ok(GameVersion::Bedrock(meta_resolve_bedrock_version(alias)?)))
+            .collect()
+    }
+
+    fn resolve_build(&self, _ver: &GameVersion, _options: &InstallOptions) -> Result<Option<String>, LibError> {
+        Ok(None)
+    }
+
+    fn install(&self, ver: &GameVersion, path: &Path, _options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+        download_bedrock_server(ver.bedrock()?, path.display().to_string(), reporter)
+    }
+
+    fn update(&self, path: &Path, _options: &InstallOptions, reporter: &dyn ProgressReporter) -> Result<bool, LibError> {
+        download_bedrock_update_server(path.display().to_string(), reporter)
+    }
+
+    fn launch_command(&self, path: &Path) -> Result<LaunchCommand, LibError> {
+        bedrock_launch_command(path)
+    }
+}
+
+fn installer_proxy_launch_command(path: &Path, software: ProxySoftware) -> Result<LaunchCommand, LibError> {
+    let instance = match instance_read_kind(path)? {
+        InstanceKind::Proxy(instance) if instance.proxy == software => instance,
+        _ => return Err(LibError::Misc(format!("{} is not a {software} instance", path.display()))),
+    };
+    Ok(LaunchCommand {
+        program: config_collect_java_bin_path(java_resolve_proxy_version(instance.proxy, &instance.version))?,
+        args: vec!["-jar".to_owned(), PROXY_JAR_NAME.to_owned()],
+        working_dir: path.to_path_buf(),
+        env: Vec::new(),
+    })
+}
+
+fn installer_paper_options(options: &InstallOptions) -> PaperOptions {
+    PaperOptions {
+        build: options.build.clone(),
+        channel: options.channel,
+    }
+}
+
+// Keeps Mojang's ordering and release times for the versions a loader supports.
+fn installer_game_versions_where(supported: impl Fn(&MinecraftVersion) -> bool) -> Result<Vec<GameVersion>, LibError> {
+    let versions = meta_fetch_game_versions_of_kind(&VersionKind::ALL)?;
+    Ok(versions.into_iter().filter(|ver| supported(ver)).map(GameVersion::Java).collect())
+}
+
+fn installer_write_instance(path: &Path, loader: Modloaders, ver: &MinecraftVersion, build: Option<String>) -> Result<(), LibError> {
+    instance_write(path, &Instance::new(loader, ver.clone(), build))
+}
+
+fn installer_read_instance(path: &Path, loader: Modloaders) -> Result<Instance, LibError> {
+    let instance = instance_read(path)?;
+    if instance.loader != loader {
+        return Err(LibError::Misc(format!("{} is a {:?} instance, not {loader:?}", path.display(), instance.loader)));
+    }
+    Ok(instance)
+}
+
+fn installer_java_bin(instance: &Instance) -> Result<String, LibError> {
+    config_collect_java_bin_path(java_resolve_version(&instance.mc_version, instance.loader)?)
+}
+
+fn installer_jar_launch_command(path: &Path, loader: Modloaders, jar: &str) -> Result<LaunchCommand, LibError> {
+    let instance = installer_read_instance(path, loader)?;
+    Ok(LaunchCommand {
+        program: installer_java_bin(&instance)?,
+        args: vec!["-jar".to_owned(), jar.to_owned(), "nogui".to_owned()],
+        working_dir: path.to_path_buf(),
+        env: Vec::new(),
+    })
+}
+
+// 1.17 and newer start through the argument files written by the installer, older versions ship a runnable jar.
+fn installer_forge_launch_command(path: &Path, instance: &Instance, build: &str) -> Result<LaunchCommand, LibError> {
+    if instance.mc_version.at_least(&[1, 17]) {
+        return installer_args_file_launch_command(path, instance, &format!("libraries/net/minecraftforge/forge/{build}"));
+    }
+    let jar = [format!("forge-{build}.jar"), format!("forge-{build}-universal.jar")]
+        .into_iter()
+        .find(|jar| path.join(jar).exists())
+        .unwrap_or_else(|| format!("forge-{build}.jar"));
+    installer_jar_launch_command(path, instance.loader, &jar)
+}
+
+// The Forge installer keeps its own jars under libraries/, named by the full build id.
+fn installer_installed_forge_build(path: &Path) -> Result<String, LibError> {
+    let forge_dir = path.join("libraries/net/minecraftforge/forge");
+    let mut builds = Vec::new();
+    if forge_dir.is_dir() {
+        for entry in fs::read_dir(&forge_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                builds.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    // Directory names like 1.20.1-47.10.0 are compared number by number, 47.10.0 sorts above 47.9.0
+    builds.sort_by_key(|build| {
+        build.split(|c: char| !c.is_ascii_digit()).filter_map(|part| part.parse::<u32>().ok()).collect::<Vec<_>>()
+    });
+    builds.pop().ok_or_else(|| LibError::Misc(format!("No Forge install found in {}", path.display())))
+}
+
+fn installer_args_file_launch_command(path: &Path, instance: &Instance, library_dir: &str) -> Result<LaunchCommand, LibError> {
+    let args_file = if cfg!(target_os = "windows") { "win_args.txt" } else { "unix_args.txt" };
+    let mut args = Vec::new();
+    if path.join("user_jvm_args.txt").exists() {
+        args.push("@user_jvm_args.txt".to_owned());
+    }
+    args.push(format!("@{library_dir}/{args_file}"));
+    args.push("nogui".to_owned());
+    Ok(LaunchCommand {
+        program: installer_java_bin(instance)?,
+        args,
+        working_dir: path.to_path_buf(),
+        env: Vec::new(),
+    })
+}
+
+//
+// Java Downloads
+//
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq)]
+pub enum JavaVersion {
+    Java8,
+    Java17,
+    Java21,
+    Java25,
+}
+
+impl fmt::Display for JavaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl JavaVersion {
+    // The smallest runtime we ship that can run code built for the given Java major version.
+    pub fn from_major(major: u32) -> JavaVersion {
+        match major {
+            0..=8 => JavaVersion::Java8,
+            9..=17 => JavaVersion::Java17,
+            18..=21 => JavaVersion::Java21,
+            _ => JavaVersion::Java25,
+        }
+    }
+}
+
+// Mojang publishes the required Java major version per Minecraft version, versions before 1.17 don't list one and run on Java 8.
+pub fn java_resolve_version(ver: &MinecraftVersion, loader: Modloaders) -> Result<JavaVersion, LibError> {
+    let data_url = download_vanilla_get_version_data_url(ver)?;
+    if data_url == "none" {
+        return Err(LibError::VersionNotFound {
+            loader: "Vanilla".to_owned(),
+            version: ver.to_string(),
+        });
+    }
+    let text = cache_fetch_text(&data_url)?;
+    let requirement: MojangJavaRequirement = serde_json::from_str(&text)?;
+    let java_version = JavaVersion::from_major(requirement.java_version.map(|java| java.major_version).unwrap_or(8));
+
+    match loader {
+        // The Forge installers and ModLauncher before 1.17 break on the Java module system
+        Modloaders::Forge | Modloaders::NeoForge | Modloaders::SpongeForge | Modloaders::Mohist | Modloaders::Arclight
+            if !ver.at_least(&[1, 17]) => Ok(JavaVersion::Java8),
+        _ => Ok(java_version),
+    }
+}
+
+// Velocity 3.4 moved to Java 21, Waterfall and BungeeCord run on any current LTS.
+pub fn java_resolve_proxy_version(software: ProxySoftware, ver: &ProxyVersion) -> JavaVersion {
+    match software {
+        ProxySoftware::Velocity if ver.numbers() >= [3, 4].as_slice() => JavaVersion::Java21,
+        ProxySoftware::Velocity if ver.numbers() >= [3].as_slice() => JavaVersion::Java17,
+        ProxySoftware::Velocity => JavaVersion::Java8,
+        ProxySoftware::Waterfall => JavaVersion::Java17,
+        ProxySoftware::BungeeCord => JavaVersion::Java21,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JavaVendor {
+    #[default]
+    Corretto,
+    Temurin,
+    Zulu,
+}
+
+impl fmt::Display for JavaVendor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// Which vendor each runtime is downloaded from, `vendor` applies to every version that isn't set explicitly.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct JavaSettings {
+    pub vendor: JavaVendor,
+    pub java8: Option<JavaVendor>,
+    pub java17: Option<JavaVendor>,
+    pub java21: Option<JavaVendor>,
+    pub java25: Option<JavaVendor>,
+    pub installations: Vec<JavaInstallation>,
+}
+
+impl JavaSettings {
+    pub fn vendor_for(&self, java_ver: JavaVersion) -> JavaVendor {
+        let vendor = match java_ver {
+            JavaVersion::Java8 => self.java8,
+            JavaVersion::Java17 => self.java17,
+            JavaVersion::Java21 => self.java21,
+            JavaVersion::Java25 => self.java25,
+        };
+        vendor.unwrap_or(self.vendor)
+    }
+}
+
+// A runtime archive for the current platform and the checksum its vendor publishes for it.
+#[derive(Debug, Clone)]
+pub struct JavaPackage {
+    pub url: String,
+    pub checksum: Checksum,
+}
+
+pub trait JavaProvider: Send + Sync {
+    fn vendor(&self) -> JavaVendor;
+
+    fn resolve_package(&self, java_ver: JavaVersion, platform: &JavaPlatform) -> Result<JavaPackage, LibError>;
+}
+
+static JAVA_PROVIDERS: &[&dyn JavaProvider] = &[
+    &CorrettoProvider,
+    &TemurinProvider,
+    &ZuluProvider,
+];
+
+pub fn java_provider_for(vendor: JavaVendor) -> Result<&'static dyn JavaProvider, LibError> {
+    JAVA_PROVIDERS
+        .iter()
+        .copied()
+        .find(|provider| provider.vendor() == vendor)
+        .ok_or_else(|| LibError::Misc(format!("No Java provider registered for {vendor}")))
+}
+
+fn java_major(java_ver: JavaVersion) -> u32 {
+    match java_ver {
+        JavaVersion::Java8 => 8,
+        JavaVersion::Java17 => 17,
+        JavaVersion::Java21 => 21,
+        JavaVersion::Java25 => 25,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JavaArch {
+    X64,
+    Aarch64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JavaLibc {
+    Glibc,
+    Musl,
+}
+
+// The host a runtime has to run on, vendors name each of these differently in their APIs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JavaPlatform {
+    pub windows: bool,
+    pub arch: JavaArch,
+    pub libc: JavaLibc,
+}
+
+impl fmt::Display for JavaPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let arch = match self.arch {
+            JavaArch::X64 => "x86_64",
+            JavaArch::Aarch64 => "aarch64",
+        };
+        match (self.windows, self.libc) {
+            (true, _) => write!(f, "windows-{arch}"),
+            (false, JavaLibc::Glibc) => write!(f, "linux-{arch}"),
+            (false, JavaLibc::Musl) => write!(f, "linux-{arch}-musl"),
+        }
+    }
+}
+
+impl JavaPlatform {
+    // Every vendor we download from calls x86_64 "x64".
+    fn vendor_arch(&self) -> &'static str {
+        match self.arch {
+            JavaArch::X64 => "x64",
+            JavaArch::Aarch64 => "aarch64",
+        }
+    }
+
+    // Corretto and Temurin call their musl builds "alpine-linux".
+    fn vendor_os(&self) -> &'static str {
+        match (self.windows, self.libc) {
+            (true, _) => "windows",
+            (false, JavaLibc::Glibc) => "linux",
+            (false, JavaLibc::Musl) => "alpine-linux",
+        }
+    }
+
+    fn archive_type(&self) -> &'static str {
+        if self.windows { "zip" } else { "tar.gz" }
+    }
+}
+
+pub fn java_detect_platform() -> Result<JavaPlatform, LibError> {
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => JavaArch::X64,
+        "aarch64" => JavaArch::Aarch64,
+        other => return Err(LibError::UnsupportedPlatform(format!("no Java runtimes are downloaded for {other} CPUs"))),
+    };
+    let windows = cfg!(target_os = "windows");
+    let libc = if !windows && java_host_is_musl() { JavaLibc::Musl } else { JavaLibc::Glibc };
+    Ok(JavaPlatform { windows, arch, libc })
+}
+
+pub const JAVA_GLIBC_LOADERS: [&str; 3] = ["/lib64/ld-linux-x86-64.so.2", "/lib/ld-linux-aarch64.so.1", "/lib64/ld-linux-aarch64.so.1"];
+
+// Alpine and other musl distros ship their dynamic loader as /lib/ld-musl-<arch>.so.1, glibc JDKs don't start there.
+// Glibc hosts can have the musl package installed too, so its loader only counts when glibc's is missing.
+fn java_host_is_musl() -> bool {
+    if JAVA_GLIBC_LOADERS.iter().any(|loader| Path::new(loader).exists()) {
+        return false;
+    }
+    match fs::read_dir("/lib") {
+        Ok(entries) => entries
+            .flatten()
+            .any(|entry| entry.file_name().to_string_lossy().starts_with("ld-musl-")),
+        Err(_) => false,
+    }
+}
+
+fn java_no_build(vendor: JavaVendor, java_ver: JavaVersion, platform: &JavaPlatform) -> LibError {
+    LibError::NoJavaBuild {
+        vendor: vendor.to_string(),
+        java: java_ver.to_string(),
+        platform: platform.to_string(),
+    }
+}
+
+// Vendor APIs answer 404 for platforms they don't build for, that is worth a clearer error than the status.
+fn java_fetch_listing(url: &str, vendor: JavaVendor, java_ver: JavaVersion, platform: &JavaPlatform) -> Result<String, LibError> {
+    match download_fetch_text(url) {
+        Err(LibError::HttpStatus { status: 404, .. }) => Err(java_no_build(vendor, java_ver, platform)),
+        result => result,
+    }
+}
+
+pub struct CorrettoProvider;
+
+impl JavaProvider for CorrettoProvider {
+    fn vendor(&self) -> JavaVendor {
+        JavaVendor::Corretto
+    }
+
+    // Corretto publishes the hash of its latest archive as a plain text file next to it.
+    fn resolve_package(&self, java_ver: JavaVersion, platform: &JavaPlatform) -> Result<JavaPackage, LibError> {
+        let arch = platform.vendor_arch();
+        let os = platform.vendor_os();
+        let file = format!("amazon-corretto-{}-{arch}-{os}-jdk.{}", java_major(java_ver), platform.archive_type());
+        let hash_url = format!("{CORRETTO_URL}/downloads/latest_sha256/{file}");
+        let published_hash = java_fetch_listing(&hash_url, self.vendor(), java_ver, platform)?;
+        match published_hash.split_whitespace().next() {
+            Some(hash) => Ok(JavaPackage {
+                url: format!("{CORRETTO_URL}/downloads/latest/{file}"),
+                checksum: Checksum::Sha256(hash.to_owned()),
+            }),
+            None => Err(LibError::Misc(format!("Empty checksum response from {hash_url}"))),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AdoptiumAsset {
+    binary: AdoptiumBinary,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdoptiumBinary {
+    package: AdoptiumPackage,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdoptiumPackage {
+    link: String,
+    checksum: String,
+}
+
+pub struct TemurinProvider;
+
+impl JavaProvider for TemurinProvider {
+    fn vendor(&self) -> JavaVendor {
+        JavaVendor::Temurin
+    }
+
+    fn resolve_package(&self, java_ver: JavaVersion, platform: &JavaPlatform) -> Result<JavaPackage, LibError> {
+        let major = java_major(java_ver);
+        let arch = platform.vendor_arch();
+        let os = platform.vendor_os();
+        let url = format!("{ADOPTIUM_API_URL}/v3/assets/latest/{major}/hotspot?os={os}&architecture={arch}&image_type=jdk&vendor=eclipse");
+        let assets: Vec<AdoptiumAsset> = serde_json::from_str(&java_fetch_listing(&url, self.vendor(), java_ver, platform)?)?;
+        match assets.into_iter().next() {
+            Some(asset) => Ok(JavaPackage {
+                url: asset.binary.package.link,
+                checksum: Checksum::Sha256(asset.binary.package.checksum),
+            }),
+            None => Err(java_no_build(self.vendor(), java_ver, platform)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ZuluPackage {
+    package_uuid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ZuluPackageDetails {
+    download_url: String,
+    sha256_hash: String,
+}
+
+pub struct ZuluProvider;
+
+impl JavaProvider for ZuluProvider {
+    fn vendor(&self) -> JavaVendor {
+        JavaVendor::Zulu
+    }
+
+    // The package listing doesn't carry hashes, those are only on the details of a single package.
+    fn resolve_package(&self, java_ver: JavaVersion, platform: &JavaPlatform) -> Result<JavaPackage, LibError> {
+        let major = java_major(java_ver);
+        let arch = platform.vendor_arch();
+        let os = match (platform.windows, platform.libc) {
+            (true, _) => "windows",
+            (false, JavaLibc::Glibc) => "linux_glibc",
+            (false, JavaLibc::Musl) => "linux_musl",
+        };
+        let archive_type = platform.archive_type();
+        let url = format!("{AZUL_API_URL}/metadata/v1/zulu/packages/?java_version={major}&os={os}&arch={arch}&archive_type={archive_type}&java_package_type=jdk&javafx_bundled=false&latest=true&release_status=ga&availability_types=CA&page_size=1");
+        let packages: Vec<ZuluPackage> = serde_json::from_str(&java_fetch_listing(&url, self.vendor(), java_ver, platform)?)?;
+        let Some(package) = packages.into_iter().next() else {
+            return Err(java_no_build(self.vendor(), java_ver, platform));
+        };
+        let details_url = format!("{AZUL_API_URL}/metadata/v1/zulu/packages/{}", package.package_uuid);
+        let details: ZuluPackageDetails = serde_json::from_str(&download_fetch_text(&details_url)?)?;
+        Ok(JavaPackage {
+            url: details.download_url,
+            checksum: Checksum::Sha256(details.sha256_hash),
+        })
+    }
+}
+
+// Returns the java binary for the runtime, downloading it first if it isn't installed yet.
+pub fn java_ensure_installed(java_ver: JavaVersion, reporter: &dyn ProgressReporter) -> Result<String, LibError> {
+    if let Ok(java_bin) = config_collect_java_bin_path(java_ver) {
+        return Ok(java_bin);
+    }
+    // A runtime installed on the host since the last --discover-java saves the download, the config is left alone
+    let major = java_major(java_ver);
+    if let Some(installation) = java_discover_installations()
+        .into_iter()
+        .find(|installation| installation.major == major && config_java_runs(Path::new(&installation.java_bin)))
+    {
+        return Ok(installation.java_bin);
+    }
+    let config = config_read_config_or_default()?;
+    let vendor = config.java.vendor_for(java_ver);
+    progress_phase(reporter, format!("{java_ver} was not found, downloading it from {vendor}..."));
+    download_java_runtime(vendor, java_ver, reporter, config.directories.java_dir)?;
+    config_collect_java_bin_path(java_ver)
+}
+
+// The java binary to run installers and launch servers for a Minecraft version and loader with.
+pub fn java_resolve_bin_path(ver: &MinecraftVersion, loader: Modloaders, reporter: &dyn ProgressReporter) -> Result<String, LibError> {
+    let java_ver = java_resolve_version(ver, loader)?;
+    java_ensure_installed(java_ver, reporter)
+}
+
+// Downloads the runtime from the given vendor into the java directory and verifies it against the vendor's checksum.
+pub fn download_java_runtime(vendor: JavaVendor, java_ver: JavaVersion, reporter: &dyn ProgressReporter, path: String) -> Result<(), LibError> {
+
+    let path_path = PathBuf::from(&path).join(java_ver.to_string());
+
+    // Resolve the published hash up front, so the archive is verified before it is moved into place
+    let platform = java_detect_platform()?;
+    let package = java_provider_for(vendor)?.resolve_package(java_ver, &platform)?;
+
+    let save_path = PathBuf::from(&path).join(format!("{java_ver}.{}", platform.archive_type()));
+    download_file(&package.url, &save_path, Some(&package.checksum), reporter)?;
+
+    // Unpacked next to the runtime and swapped in afterwards, so files of an older build never linger
+    let staging_path = PathBuf::from(&path).join(format!("{java_ver}.partial"));
+    if staging_path.exists() {
+        fs::remove_dir_all(&staging_path)?;
+    }
+    fs::create_dir_all(&staging_path)?;
+    let result = download_java_install_staged(save_path, &staging_path, &path_path, reporter);
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&staging_path);
+    }
+    result
+}
+
+fn download_java_install_staged(save_path: PathBuf, staging_path: &Path, path_path: &Path, reporter: &dyn ProgressReporter) -> Result<(), LibError> {
+    reporter.report(ProgressEvent::Extracting { file: save_path.to_string_lossy().to_string() });
+
+    #[cfg(target_os = "linux")]
+    download_java_unpack_targz(save_path, staging_path.to_path_buf())?;
+
+    #[cfg(target_os = "windows")]
+    download_java_unpack_zip(save_path, staging_path.to_path_buf())?;
 
-    if let Some (spinner) = &spinner {
-        spinner.enable_steady_tick(Duration::from_millis(100));
-        spinner.set_style(
-            ProgressStyle::default_spinner()
-            .tick_chars("|/-\\")
-            .template("{spinner} {msg}")
-            .unwrap(),
-        );
+    if path_path.exists() {
+        fs::remove_dir_all(path_path)?;
     }
+    fs::rename(staging_path, path_path)?;
+    Ok(())
+}
 
-    let java_cmd = config_collect_java_bin_path(JavaVersion::Java17)?;
+#[cfg(target_os = "linux")]
+fn download_java_unpack_targz(targz_path: PathBuf, save_path: PathBuf) -> Result<(), LibError> {
+    let archive_error = |e: std::io::Error| LibError::Archive {
+        file: targz_path.to_string_lossy().to_string(),
+        reason: e.to_string(),
+    };
+    let targz = File::open(targz_path.clone())?;
+    let tar = GzDecoder::new(targz);
+    let mut archive =Archive::new(tar);
+    for entry in archive.entries().map_err(archive_error)? {
+        let mut entry = entry.map_err(archive_error)?;
 
-    let mut child = Command::new(java_cmd)
-    .args(["-jar", "installer.jar", "--installServer"])
-    .current_dir(path)
-    .stdin(Stdio::null())
-    .stdout(Stdio::null())
-    .stderr(Stdio::null())
-    .spawn()?;
+        let path = entry.path().map_err(archive_error)?;
+        let stripped: PathBuf = path.components().skip(1).collect();
 
-    
-    child.wait()?;
+        if stripped.as_os_str().is_empty() {
+            continue;
+        }
 
-    if let Some (spinner) = &spinner {
-        spinner.finish();
+        entry.unpack(save_path.join(stripped)).map_err(archive_error)?;
     }
-    return Ok(());
-    
+    fs::remove_file(targz_path)?;
+    Ok(())
 }
 
-fn download_forge_installer(mc_ver: String, path:String, forge_ver: String, term: bool) -> Result<(), LibError>{
-    let url;
-    if mc_ver == "1.9.4" || mc_ver == "1.8.9" || mc_ver == "1.7.10"{
-        url = format!("https://maven.minecraftforge.net/net/minecraftforge/forge/{}-{}-{}/forge-{}-{}-{}-installer.jar", mc_ver, forge_ver, mc_ver, mc_ver, forge_ver, mc_ver);
-    } else {
-        url = format!("https://maven.minecraftforge.net/net/minecraftforge/forge/{}-{}/forge-{}-{}-installer.jar", mc_ver, forge_ver, mc_ver, forge_ver)
-    }
-    let mut response = ureq::get(&url).call()?;
+#[cfg(target_os = "windows")]
+fn download_java_unpack_zip(zip_path: PathBuf, save_path: PathBuf) -> Result<(), LibError> {
+    let archive_error = |e: zip::result::ZipError| LibError::Archive {
+        file: zip_path.to_string_lossy().to_string(),
+        reason: e.to_string(),
+    };
+    let file = File::open(&zip_path)?;
+    let mut archive = ZipArchive::new(file).map_err(archive_error)?;
 
-        let size = response.body().content_length().unwrap_or(0);
-        
-        let mut reader = response.body_mut().as_reader();
-        let save_path = Path::new(&path).join("installer.jar");
-        let mut server_jar = File::create(save_path)?;
-        let progress = if term {
-            Some(ProgressBar::new(size))
-        } else {
-            None
-        };
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(archive_error)?;
 
-        if let Some(pb) = &progress {
-            pb.set_style(
-                ProgressStyle::default_bar()
-                .template(
-                    "{bar:80.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})"
-                )
-                .unwrap()
-                .progress_chars("=> "),
-            );
+        // Skip directories
+        let path = match entry.enclosed_name() {
+            Some(path) => path,
+            None => continue, // security: skip paths with traversal
+        };
 
+        // Strip the top-level directory
+        let stripped: PathBuf = path.components().skip(1).collect();
+        if stripped.as_os_str().is_empty() {
+            continue;
         }
 
-        let mut buffer = [0u8; 8 * 1024];
-        loop {
-            let n = reader.read(&mut buffer).unwrap();
-            if n == 0 {
-                break;
-            }
-            server_jar.write_all(&buffer[..n]).unwrap();
-            if let Some(pb) = &progress {
-                pb.inc(n as u64);
+        let out_path = save_path.join(&stripped);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path)?;
+        } else {
+            if let Some(parent) = out_path.parent() {
+                fs::create_dir_all(parent)?;
             }
+            let mut outfile = File::create(&out_path)?;
+            std::io::copy(&mut entry, &mut outfile)?;
         }
-        Ok(())
+    }
+
+    fs::remove_file(zip_path)?;
+    Ok(())
 }
 
 //
-// Neoforge Server
+// Java Discovery
 //
 
-pub fn download_neoforge_server(path: String, ver: String, term: bool, neoforge_ver: String) -> Result<(), LibError> {
-    download_neoforge_installer(ver, neoforge_ver, path.clone(), term)?;
+pub const JAVA_SYSTEM_DIR: &str = "/usr/lib/jvm";
 
-    if term {
-        println!("Installing NeoForge Server...");
-    }
+// A runtime that was already installed on the host, registered in the config by `java_register_installations`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct JavaInstallation {
+    pub home: String,
+    pub java_bin: String,
+    pub major: u32,
+    pub version: String,
+    pub vendor: String,
+}
 
-    let spinner = if term {
-            Some(ProgressBar::new_spinner())
-        } else {
-            None
+// JAVA_HOME first, then every runtime below the distro JVM directory and SDKMAN's Java candidates.
+fn java_candidate_homes() -> Vec<PathBuf> {
+    let mut homes = Vec::new();
+    if let Ok(java_home) = std::env::var("JAVA_HOME") {
+        homes.push(PathBuf::from(java_home));
+    }
+    let sdkman_dir = match std::env::var("SDKMAN_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => PathBuf::from(config_fetch_directories().home_dir).join(".sdkman"),
     };
-
-    if let Some (spinner) = &spinner {
-        spinner.enable_steady_tick(Duration::from_millis(100));
-        spinner.set_style(
-            ProgressStyle::default_spinner()
-            .tick_chars("|/-\\")
-            .template("{spinner} {msg}")
-            .unwrap(),
-        );
+    for root in [PathBuf::from(JAVA_SYSTEM_DIR), sdkman_dir.join("candidates").join("java")] {
+        if let Ok(entries) = fs::read_dir(root) {
+            homes.extend(entries.flatten().map(|entry| entry.path()));
+        }
     }
-    let java_cmd = config_collect_java_bin_path(JavaVersion::Java17)?;
-
-    let mut child = Command::new(java_cmd)
-    .args(["-jar", "installer.jar", "--installServer"])
-    .current_dir(path)
-    .stdin(Stdio::null())
-    .stdout(Stdio::null())
-    .stderr(Stdio::null())
-    .spawn()?;
-
-    child.wait()?;
+    homes
+}
 
-    if let Some (spinner) = &spinner {
-        spinner.finish();
+pub fn java_discover_installations() -> Vec<JavaInstallation> {
+    let mut found: Vec<JavaInstallation> = Vec::new();
+    for home in java_candidate_homes() {
+        // Symlinks like default-java or SDKMAN's current point at runtimes that are listed anyway
+        let Ok(home) = home.canonicalize() else {
+            continue;
+        };
+        let home_str = home.to_string_lossy().to_string();
+        if found.iter().any(|installation| installation.home == home_str) {
+            continue;
+        }
+        let java_bin = home.join("bin").join(if cfg!(target_os = "windows") { "java.exe" } else { "java" });
+        if !java_bin.is_file() {
+            continue;
+        }
+        let details = fs::read_to_string(home.join("release"))
+            .ok()
+            .and_then(|release| java_parse_release(&release))
+            .or_else(|| java_query_version(&java_bin));
+        if let Some((version, vendor)) = details
+            && let Some(major) = java_parse_major(&version)
+        {
+            found.push(JavaInstallation {
+                home: home_str,
+                java_bin: java_bin.to_string_lossy().to_string(),
+                major,
+                version,
+                vendor,
+            });
+        }
     }
-    return Ok(());
-
+    found
 }
 
-fn download_neoforge_installer(_ver: String, neoforge_ver: String, path: String, term: bool) -> Result<(), LibError>{
-    println!("{}", neoforge_ver);
-    let url = format!("https://maven.neoforged.net/releases/net/neoforged/neoforge/{neoforge_ver}/neoforge-{neoforge_ver}-installer.jar");
-    let mut response = ureq::get(&url).call()?;
-
-        let size = response.body().content_length().unwrap_or(0);
-        
-        let mut reader = response.body_mut().as_reader();
-        let save_path = Path::new(&path).join("installer.jar");
-        let mut server_jar = File::create(save_path)?;
-        let progress = if term {
-            Some(ProgressBar::new(size))
-        } else {
-            None
-        };
+// Adds what is on the host now to the registered runtimes. Ones found through another environment's JAVA_HOME
+// stay registered as long as they exist, only removed runtimes are dropped.
+pub fn java_register_installations() -> Result<Vec<JavaInstallation>, LibError> {
+    let mut config = config_read_config()?;
+    let mut found = java_discover_installations();
+    for installation in config.java.installations {
+        if Path::new(&installation.java_bin).is_file() && !found.iter().any(|known| known.home == installation.home) {
+            found.push(installation);
+        }
+    }
+    config.java.installations = found.clone();
+    config_write_config(&config)?;
+    Ok(found)
+}
 
-        if let Some(pb) = &progress {
-            pb.set_style(
-                ProgressStyle::default_bar()
-                .template(
-                    "{bar:80.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})"
-                )
-                .unwrap()
-                .progress_chars("=> "),
-            );
+// The `release` file in a runtime's home has lines like JAVA_VERSION="17.0.9" and IMPLEMENTOR="Eclipse Adoptium".
+fn java_parse_release(release: &str) -> Option<(String, String)> {
+    let value = |key: &str| {
+        release.lines().find_map(|line| {
+            line.strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(|value| value.trim().trim_matches('"').to_owned())
+        })
+    };
+    let version = value("JAVA_VERSION")?;
+    Some((version, value("IMPLEMENTOR").unwrap_or_else(|| "Unknown".to_owned())))
+}
 
-        }
+// Runtimes without a `release` file print e.g. `openjdk version "17.0.9"` followed by the runtime name on stderr.
+// JAVA_TOOL_OPTIONS makes the JVM print a "Picked up ..." line before it.
+fn java_query_version(java_bin: &Path) -> Option<(String, String)> {
+    let output = Command::new(java_bin)
+        .arg("-version")
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stderr);
+    let mut lines = text.lines().skip_while(|line| !line.contains("version \""));
+    let version = lines.next()?.split('"').nth(1)?.to_owned();
+    let vendor = match lines.next() {
+        Some(line) => line.split(" (build").next().unwrap_or(line).trim().to_owned(),
+        None => "Unknown".to_owned(),
+    };
+    Some((version, vendor))
+}
 
-        let mut buffer = [0u8; 8 * 1024];
-        loop {
-            let n = reader.read(&mut buffer).unwrap();
-            if n == 0 {
-                break;
-            }
-            server_jar.write_all(&buffer[..n]).unwrap();
-            if let Some(pb) = &progress {
-                pb.inc(n as u64);
-            }
-        }
-        Ok(())
+// Java 8 and older report themselves as 1.8.0_392, newer ones start with the major version.
+fn java_parse_major(version: &str) -> Option<u32> {
+    let version = version.strip_prefix("1.").unwrap_or(version);
+    let digits: String = version.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
 }
 
 //
-// Fabric Server
+// Metadata
 //
 
-pub fn download_fabric_server(mc_ver: String, path: String, term: bool) -> Result<(), LibError> {
-    download_fabric_installer(path.clone(), term)?;
-    if term {
-        println!("Installing Fabric Server...");
-    }
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Modloaders {
+    Vanilla,
+    Forge,
+    NeoForge,
+    Fabric,
+    Paper,
+    Folia,
+    Purpur,
+    Pufferfish,
+    Quilt,
+    SpongeVanilla,
+    SpongeForge,
+    Mohist,
+    Arclight,
+    Youer,
+}
 
-    let spinner = if term {
-            Some(ProgressBar::new_spinner())
-        } else {
-            None
-    };
+// Folders in an instance that hold user content, the loader decides which ones it reads.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContentKind {
+    Mods,
+    Plugins,
+}
 
-    if let Some (spinner) = &spinner {
-        spinner.enable_steady_tick(Duration::from_millis(100));
-        spinner.set_style(
-            ProgressStyle::default_spinner()
-            .tick_chars("|/-\\")
-            .template("{spinner} {msg}")
-            .unwrap(),
-        );
+impl ContentKind {
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            ContentKind::Mods => "mods",
+            ContentKind::Plugins => "plugins",
+        }
     }
-    let java_cmd = config_collect_java_bin_path(JavaVersion::Java17)?;
-
-    let mut child = Command::new(java_cmd)
-    .args(["-jar", "installer.jar", "server", "-mcversion", &mc_ver, "-dir", &path])
-    .current_dir(path)
-    .stdin(Stdio::null())
-    .stdout(Stdio::null())
-    .stderr(Stdio::null())
-    .spawn()?;
-
-    child.wait()?;
+}
 
-    if let Some (spinner) = &spinner {
-        spinner.finish();
+impl Modloaders {
+    // Hybrids run Forge/NeoForge mods and Bukkit plugins side by side.
+    pub fn content_kinds(&self) -> &'static [ContentKind] {
+        match self {
+            Modloaders::Vanilla => &[],
+            Modloaders::Forge | Modloaders::NeoForge | Modloaders::Fabric | Modloaders::Quilt | Modloaders::SpongeForge => &[ContentKind::Mods],
+            Modloaders::Paper | Modloaders::Folia | Modloaders::Purpur | Modloaders::Pufferfish | Modloaders::SpongeVanilla => &[ContentKind::Plugins],
+            Modloaders::Mohist | Modloaders::Arclight | Modloaders::Youer => &[ContentKind::Mods, ContentKind::Plugins],
+        }
     }
-    return Ok(());
+}
+ 
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq)]
+pub enum VersionKind {
+    Release,
+    Snapshot,
+    OldBeta,
+    OldAlpha,
 }
 
-fn download_fabric_installer(path: String, term: bool) -> Result<(), LibError> {
-    let downlad_url = String::from("https://maven.fabricmc.net/net/fabricmc/fabric-installer/1.0.0/fabric-installer-1.0.0.jar");
-
-        let mut response = ureq::get(downlad_url).call()?;
+impl VersionKind {
+    pub const ALL: [VersionKind; 4] = [VersionKind::Release, VersionKind::Snapshot, VersionKind::OldBeta, VersionKind::OldAlpha];
 
-        let size = response.body().content_length().unwrap_or(0);
-        
-        let mut reader = response.body_mut().as_reader();
-        let save_path = Path::new(&path).join("installer.jar");
-        let mut server_jar = File::create(save_path)?;
+    // The `type` value used in Mojang's version manifest. Pre-releases and release candidates are listed as snapshots.
+    pub fn manifest_type(&self) -> &'static str {
+        match self {
+            VersionKind::Release => "release",
+            VersionKind::Snapshot => "snapshot",
+            VersionKind::OldBeta => "old_beta",
+            VersionKind::OldAlpha => "old_alpha",
+        }
+    }
+}
 
-        let progress = if term {
-            Some(ProgressBar::new(size))
-        } else {
-            None
-        };        
+pub const LATEST_RELEASE_ALIAS: &str = "latest.release";
+pub const LATEST_SNAPSHOT_ALIAS: &str = "latest.snapshot";
 
-        if let Some(pb) = &progress {
-            pb.set_style(
-                ProgressStyle::default_bar()
-                .template(
-                    "{bar:80.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})"
-                )
-                .unwrap()
-                .progress_chars("=> "),
-            );
+// Which build of a loader/server software to install for a Minecraft version.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum BuildSelection {
+    #[default]
+    Recommended,
+    Latest,
+    Pinned(String),
+}
 
+impl fmt::Display for BuildSelection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildSelection::Recommended => write!(f, "recommended"),
+            BuildSelection::Latest => write!(f, "latest"),
+            BuildSelection::Pinned(build) => write!(f, "{build}"),
         }
+    }
+}
 
-        let mut buffer = [0u8; 8 * 1024];
-        loop {
-            let n = reader.read(&mut buffer).unwrap();
-            if n == 0 {
-                break;
-            }
-            server_jar.write_all(&buffer[..n]).unwrap();
-            if let Some(pb) = &progress {
-                pb.inc(n as u64);
-            }
+impl FromStr for BuildSelection {
+    type Err = LibError;
+
+    fn from_str(s: &str) -> Result<BuildSelection, LibError> {
+        match s.trim() {
+            "" => Err(LibError::Misc("empty build selection".to_owned())),
+            "recommended" | "stable" => Ok(BuildSelection::Recommended),
+            "latest" => Ok(BuildSelection::Latest),
+            build => Ok(BuildSelection::Pinned(build.to_owned())),
         }
-        Ok(())
+    }
 }
 
-//
-// Paper Server
-//
+pub fn meta_fetch_game_versions() -> Result<Vec<MinecraftVersion>, LibError> {
+    meta_fetch_game_versions_of_kind(&[VersionKind::Release])
+}
 
-#[derive(Debug, Deserialize)]
-pub struct PaperProjectVersions {
-    pub project_id: String,
-    pub project_name: String,
-    pub versions: Vec<String>,
-    pub builds: Option<Vec<u32>>,
+// Newest first, ordered by Mojang's release time.
+pub fn meta_fetch_game_versions_of_kind(kinds: &[VersionKind]) -> Result<Vec<MinecraftVersion>, LibError> {
+    let manifest = download_vanilla_fetch_available_vannila_versions()?;
+    let mut versions = manifest.versions
+        .into_iter()
+        .filter(|ver| kinds.iter().any(|kind| kind.manifest_type() == ver.kind))
+        .map(|ver| Ok(MinecraftVersion::parse(&ver.id)?.with_release_time(&ver.release_time)))
+        .collect::<Result<Vec<MinecraftVersion>, LibError>>()?;
+    // Every manifest entry has a release time, so every pair compares
+    versions.sort_by(|a, b| b.partial_cmp(a).unwrap_or(CmpOrdering::Equal));
+    Ok(versions)
 }
-#[derive(Debug, Deserialize)]
-pub struct PaperProjectBuilds {
-    pub project_id: String,
-    pub project_name: String,
-    pub builds: Option<Vec<u32>>,
+
+// Looks a version up in Mojang's manifest, resolving `latest.release` and `latest.snapshot` on the way.
+pub fn meta_resolve_minecraft_version(ver: &str) -> Result<MinecraftVersion, LibError> {
+    let manifest = download_vanilla_fetch_available_vannila_versions()?;
+    let id = match ver.trim() {
+        LATEST_RELEASE_ALIAS => manifest.latest.release.as_str(),
+        LATEST_SNAPSHOT_ALIAS => manifest.latest.snapshot.as_str(),
+        id => id,
+    };
+    match manifest.versions.iter().find(|entry| entry.id == id) {
+        Some(entry) => Ok(MinecraftVersion::parse(&entry.id)?.with_release_time(&entry.release_time)),
+        None => Err(LibError::VersionNotFound {
+            loader: "Vanilla".to_owned(),
+            version: id.to_owned(),
+        }),
+    }
 }
 
-pub fn download_paper_server(ver: String, path:String, term: bool, folia: bool) -> Result<(), LibError>{
+//
+// Minecraft Versions
+//
 
-    let build = download_paper_fetch_latest_build(ver.clone(), folia)?;
-    if build != 0 {
+// Minecraft, proxy and Bedrock versions are all identified by their id string and only differ in how they order.
+macro_rules! version_id_impls {
+    ($version:ident) => {
+        impl PartialEq for $version {
+            fn eq(&self, other: &$version) -> bool {
+                self.id == other.id
+            }
+        }
 
-        //The Download
-        let downlad_url;
-        if folia {
-            downlad_url = format!("https://api.papermc.io/v2/projects/folia/versions/{ver}/builds/{build}/downloads/folia-{ver}-{build}.jar");
-        } else {
-            downlad_url =  format!("https://api.papermc.io/v2/projects/paper/versions/{ver}/builds/{build}/downloads/paper-{ver}-{build}.jar");
-        };
+        impl Eq for $version {}
 
-        let mut response = ureq::get(downlad_url).call()?;
-        
-        let mut reader = response.body_mut().as_reader();
-        let save_path = Path::new(&path).join("server.jar");
-        let mut server_jar = File::create(save_path)?;
+        impl std::hash::Hash for $version {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.id.hash(state);
+            }
+        }
 
-        let progress = if term {
-            Some(ProgressBar::new_spinner())
-        } else {
-            None
-        };        
+        impl fmt::Display for $version {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.id)
+            }
+        }
 
-        if let Some(pb) = &progress {
-            pb.enable_steady_tick(Duration::from_millis(100));
-            pb.set_style(
-                ProgressStyle::default_spinner()
-                .tick_chars("|/-\\")
-                .template(
-                    "{bytes} ({bytes_per_sec}, {eta})"
-                )
-                .unwrap()
-            );
+        impl FromStr for $version {
+            type Err = LibError;
 
+            fn from_str(s: &str) -> Result<$version, LibError> {
+                $version::parse(s)
+            }
         }
 
-        let mut buffer = [0u8; 8 * 1024];
-        loop {
-            let n = reader.read(&mut buffer)?;
-            if n == 0 {
-                break;
-            }
-            server_jar.write_all(&buffer[..n])?;
-            if let Some(pb) = &progress {
-                pb.inc(n as u64);
+        impl Serialize for $version {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.id)
             }
         }
-        if let Some (pb) = &progress {
-        pb.finish();
+
+        impl<'de> Deserialize<'de> for $version {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$version, D::Error> {
+                let id = String::deserialize(deserializer)?;
+                $version::parse(&id).map_err(de::Error::custom)
+            }
         }
-        Ok(())
-    } else {
-        return Err(LibError::Ver(ver));
-    }
+    };
 }
 
-fn download_paper_fetch_latest_build(ver: String, folia: bool) -> Result<u32, LibError> {
-    let project = download_paper_fetch_versions(folia)?;
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MinecraftVersionKind {
+    Release,
+    PreRelease(u32),
+    ReleaseCandidate(u32),
+    // `26.1-snapshot-1` style snapshots, which carry the release they lead up to
+    NumberedSnapshot(u32),
+    // `24w14a` style weekly snapshots
+    Snapshot { year: u32, week: u32, suffix: String },
+    // Anything else: old alphas/betas, combat tests, april fools versions...
+    Other,
+}
 
-    let mut result: u32 = 0;
-    for v in project.versions {
-        if v == ver {
-            let url = if folia {
-                "https://api.papermc.io/v2/projects/folia/versions/"
-            } else {
-                "https://api.papermc.io/v2/projects/paper/versions/"
-            };
-            let mut response = ureq::get(url.to_owned()+&ver)
-            .call()?;
-            
-            let body = response.body_mut();
-            let text = body.read_to_string()?;
-            let project2: PaperProjectBuilds = serde_json::from_str(&text).unwrap();
-            if let Some(builds) = project2.builds {
-                result = builds[builds.len()-1]
-            }
-        }
-    }
-    return Ok(result);
+#[derive(Clone, Debug)]
+pub struct MinecraftVersion {
+    id: String,
+    numbers: Vec<u32>,
+    kind: MinecraftVersionKind,
+    release_time: Option<String>,
 }
 
+impl MinecraftVersion {
+    pub fn parse(id: &str) -> Result<MinecraftVersion, LibError> {
+        let id = id.trim();
+        if id.is_empty() {
+            return Err(LibError::Ver("empty Minecraft version".to_owned()));
+        }
 
-fn download_paper_fetch_versions(folia: bool) -> Result<PaperProjectVersions, LibError> {
-    let url = if folia {
-        "https://api.papermc.io/v2/projects/folia"
-    } else {
-        "https://api.papermc.io/v2/projects/paper"
-    };
+        let (numbers, kind) = if let Some(numbers) = minecraft_version_parse_numbers(id) {
+            (numbers, MinecraftVersionKind::Release)
+        } else if let Some((base, n)) = minecraft_version_split_suffix(id, &["-pre", " Pre-Release "]) {
+            (base, MinecraftVersionKind::PreRelease(n))
+        } else if let Some((base, n)) = minecraft_version_split_suffix(id, &["-rc"]) {
+            (base, MinecraftVersionKind::ReleaseCandidate(n))
+        } else if let Some((base, n)) = minecraft_version_split_suffix(id, &["-snapshot-"]) {
+            (base, MinecraftVersionKind::NumberedSnapshot(n))
+        } else if let Some(kind) = minecraft_version_parse_weekly_snapshot(id) {
+            (Vec::new(), kind)
+        } else {
+            (Vec::new(), MinecraftVersionKind::Other)
+        };
 
-    let mut response = ureq::get(url).call()?;
+        Ok(MinecraftVersion {
+            id: id.to_owned(),
+            numbers,
+            kind,
+            release_time: None,
+        })
+    }
 
-    let body = response.body_mut();
-    let text = body.read_to_string()?;
+    // Anchors the version on its release time from Mojang's manifest.
+    pub fn with_release_time(mut self, release_time: &str) -> MinecraftVersion {
+        self.release_time = Some(release_time.to_owned());
+        self
+    }
 
-    let project: PaperProjectVersions = serde_json::from_str(&text).unwrap();
-    return Ok(project);
-}
+    pub fn id(&self) -> &str {
+        &self.id
+    }
 
-//
-// Java Downloads
-//
+    pub fn kind(&self) -> &MinecraftVersionKind {
+        &self.kind
+    }
 
-pub const LINUX_JAVA_8_URL: &str = "https://corretto.aws/downloads/latest/amazon-corretto-8-x64-linux-jdk.tar.gz";
-pub const LINUX_JAVA_17_URL: &str = "https://corretto.aws/downloads/latest/amazon-corretto-17-x64-linux-jdk.tar.gz";
-pub const LINUX_JAVA_21_URL: &str = "https://corretto.aws/downloads/latest/amazon-corretto-21-x64-linux-jdk.tar.gz";
-pub const LINUX_JAVA_25_URL: &str = "https://corretto.aws/downloads/latest/amazon-corretto-25-x64-linux-jdk.tar.gz";
+    // The numeric part of releases, pre-releases and release candidates, e.g. [1, 20, 5] for 1.20.5-rc1
+    pub fn numbers(&self) -> &[u32] {
+        &self.numbers
+    }
 
-pub const LINUX_JAVA_8_SHA256: &str = "https://corretto.aws/downloads/latest_sha256/amazon-corretto-8-x64-linux-jdk.tar.gz";
-pub const LINUX_JAVA_17_SHA256: &str = "https://corretto.aws/downloads/latest_sha256/amazon-corretto-17-x64-linux-jdk.tar.gz";
-pub const LINUX_JAVA_21_SHA256: &str = "https://corretto.aws/downloads/latest_sha256/amazon-corretto-21-x64-linux-jdk.tar.gz";
-pub const LINUX_JAVA_25_SHA256: &str = "https://corretto.aws/downloads/latest_sha256/amazon-corretto-25-x64-linux-jdk.tar.gz";
+    pub fn release_time(&self) -> Option<&str> {
+        self.release_time.as_deref()
+    }
 
-pub const WINDOWS_JAVA_8_URL: &str = "https://corretto.aws/downloads/latest/amazon-corretto-8-x64-windows-jdk.zip";
-pub const WINDOWS_JAVA_17_URL: &str = "https://corretto.aws/downloads/latest/amazon-corretto-17-x64-windows-jdk.zip";
-pub const WINDOWS_JAVA_21_URL: &str = "https://corretto.aws/downloads/latest/amazon-corretto-21-x64-windows-jdk.zip";
-pub const WINDOWS_JAVA_25_URL: &str = "https://corretto.aws/downloads/latest/amazon-corretto-25-x64-windows-jdk.zip";
+    pub fn is_release(&self) -> bool {
+        self.kind == MinecraftVersionKind::Release
+    }
 
-pub const WINDOWS_JAVA_8_SHA256: &str = "https://corretto.aws/downloads/latest_sha256/amazon-corretto-8-x64-windows-jdk.zip";
-pub const WINDOWS_JAVA_17_SHA256: &str = "https://corretto.aws/downloads/latest_sha256/amazon-corretto-17-x64-windows-jdk.zip";
-pub const WINDOWS_JAVA_21_SHA256: &str = "https://corretto.aws/downloads/latest_sha256/amazon-corretto-21-x64-windows-jdk.zip";
-pub const WINDOWS_JAVA_25_SHA256: &str = "https://corretto.aws/downloads/latest_sha256/amazon-corretto-25-x64-windows-jdk.zip";
+    // Compares the numeric part only, so 1.20.5-rc1 is "at least" 1.20.5.
+    pub fn at_least(&self, numbers: &[u32]) -> bool {
+        !self.numbers.is_empty() && minecraft_version_cmp_numbers(&self.numbers, numbers) != CmpOrdering::Less
+    }
 
-#[derive(Clone, Copy, Debug, ValueEnum, PartialEq)]
-pub enum JavaVersion {
-    Java8,
-    Java17,
-    Java21,
-    Java25,
-}
+    // Only versions of the same line compare by their components: weekly snapshots among themselves,
+    // releases with their pre-releases, release candidates and numbered snapshots.
+    fn structural_cmp(&self, other: &MinecraftVersion) -> Option<CmpOrdering> {
+        use MinecraftVersionKind::*;
+        let stage = |kind: &MinecraftVersionKind| match kind {
+            NumberedSnapshot(n) => (0, *n),
+            PreRelease(n) => (1, *n),
+            ReleaseCandidate(n) => (2, *n),
+            _ => (3, 0),
+        };
 
-impl fmt::Display for JavaVersion {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match (&self.kind, &other.kind) {
+            (Snapshot { year: y1, week: w1, suffix: s1 }, Snapshot { year: y2, week: w2, suffix: s2 }) => {
+                Some((y1, w1, s1).cmp(&(y2, w2, s2)))
+            }
+            (Snapshot { .. } | Other, _) | (_, Snapshot { .. } | Other) => None,
+            _ => Some(
+                minecraft_version_cmp_numbers(&self.numbers, &other.numbers)
+                    .then_with(|| stage(&self.kind).cmp(&stage(&other.kind))),
+            ),
+        }
     }
 }
 
-pub fn download_java_openjdk_amazon_correto(url: &str, hash: &str, term: bool, path: String, java_ver: JavaVersion) -> Result<(), LibError> {
-
-    let path_path = PathBuf::from(&path).join(java_ver.to_string());
-    if !path_path.exists() {
-        fs::create_dir_all(path_path.clone())?;
+// Versions resolved through Mojang's manifest are ordered by their release time, so 24w14a lands between 1.20.4
+// and 1.20.5. Without both release times a weekly snapshot and a release can't be placed and don't compare.
+impl PartialOrd for MinecraftVersion {
+    fn partial_cmp(&self, other: &MinecraftVersion) -> Option<CmpOrdering> {
+        if self.id == other.id {
+            return Some(CmpOrdering::Equal);
+        }
+        let ordering = match (&self.release_time, &other.release_time) {
+            (Some(a), Some(b)) => a.cmp(b).then_with(|| self.structural_cmp(other).unwrap_or(CmpOrdering::Equal)),
+            _ => self.structural_cmp(other)?,
+        };
+        Some(ordering.then_with(|| self.id.cmp(&other.id)))
     }
+}
 
-    let mut response = ureq::get(url).call()?;
-    let size = response.body().content_length().unwrap_or(0);
-        
-    let mut reader = response.body_mut().as_reader();
-    let save_path = Path::new(&path).join(java_ver.to_string()+"/java.tar.gz");
-    let mut java_tar = File::create(save_path.clone())?;
+version_id_impls!(MinecraftVersion);
 
-    let progress = if term {
-        Some(ProgressBar::new(size))
-    } else {
-        None
-    };        
-
-    if let Some(pb) = &progress {
-        pb.set_style(
-            ProgressStyle::default_bar()
-            .template(
-                "{bar:80.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})"
-            )
-            .unwrap()
-            .progress_chars("=> "),
-        );
+fn minecraft_version_parse_numbers(s: &str) -> Option<Vec<u32>> {
+    if !s.contains('.') {
+        return None;
     }
+    s.split('.').map(|part| part.parse::<u32>().ok()).collect()
+}
 
-    let mut buffer = [0u8; 8 * 1024];
-    loop {
-        let n = reader.read(&mut buffer).unwrap();
-        if n == 0 {
-            break;
-        }
-        java_tar.write_all(&buffer[..n]).unwrap();
-        if let Some(pb) = &progress {
-            pb.inc(n as u64);
+fn minecraft_version_split_suffix(id: &str, separators: &[&str]) -> Option<(Vec<u32>, u32)> {
+    for separator in separators {
+        if let Some((base, n)) = id.split_once(separator)
+            && let (Some(base), Ok(n)) = (minecraft_version_parse_numbers(base), n.parse::<u32>()) {
+            return Some((base, n));
         }
     }
+    None
+}
 
-    if term {
-        println!("Verifying Integrety...");
+fn minecraft_version_parse_weekly_snapshot(id: &str) -> Option<MinecraftVersionKind> {
+    let (year, rest) = id.split_once('w')?;
+    if year.len() != 2 || rest.len() < 3 {
+        return None;
     }
+    let (week, suffix) = (rest.get(..2)?, rest.get(2..)?);
+    Some(MinecraftVersionKind::Snapshot {
+        year: year.parse().ok()?,
+        week: week.parse().ok()?,
+        suffix: suffix.to_owned(),
+    })
+}
 
-    let mut sha_response = ureq::get(hash).call()?;
-    let sha_body = sha_response.body_mut();
-    let downloaded_hash = sha_body.read_to_string()?;
-    let local_hash = try_digest(Path::new(&path).join(java_ver.to_string()+"/java.tar.gz"))?;
-    if downloaded_hash == local_hash {
-        if term {
-            println!("Done!");
-        }        
-    } else {
-        LibError::Misc("Could not verify the Integrety of the file!".to_owned());
+fn minecraft_version_cmp_numbers(a: &[u32], b: &[u32]) -> CmpOrdering {
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != CmpOrdering::Equal {
+            return ordering;
+        }
     }
+    CmpOrdering::Equal
+}
 
-    if term {
-        println!("Extracting Archive...");
-    }
+//
+// Proxy Versions
+//
 
-    let spinner = if term {
-            Some(ProgressBar::new_spinner())
-        } else {
-            None
-    };
+// Proxy versions don't follow Minecraft's: Velocity has 3.4.0-SNAPSHOT, Waterfall 1.21 and BungeeCord only build numbers.
+#[derive(Clone, Debug)]
+pub struct ProxyVersion {
+    id: String,
+    numbers: Vec<u32>,
+    snapshot: bool,
+}
 
-    if let Some (spinner) = &spinner {
-        spinner.enable_steady_tick(Duration::from_millis(100));
-        spinner.set_style(
-            ProgressStyle::default_spinner()
-            .tick_chars("|/-\\")
-            .template("{spinner} {msg}")
-            .unwrap(),
-        );
+impl ProxyVersion {
+    pub fn parse(id: &str) -> Result<ProxyVersion, LibError> {
+        let id = id.trim();
+        let (base, suffix) = match id.split_once('-') {
+            Some((base, suffix)) => (base, Some(suffix)),
+            None => (id, None),
+        };
+        let numbers = base
+            .split('.')
+            .map(|part| part.parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()
+            .ok_or_else(|| LibError::Ver(format!("invalid proxy version {id}")))?;
+        Ok(ProxyVersion {
+            id: id.to_owned(),
+            numbers,
+            snapshot: suffix.is_some(),
+        })
     }
 
-    #[cfg(target_os = "linux")]
-    download_java_unpack_targz(save_path, path_path)?;
-
-    #[cfg(target_os = "windows")]
-    download_java_unpack_zip(save_path, path_path)?;
+    pub fn id(&self) -> &str {
+        &self.id
+    }
 
-    if let Some (spinner) = &spinner {
-        spinner.finish();
-        println!("Done!");
+    pub fn numbers(&self) -> &[u32] {
+        &self.numbers
     }
-    Ok(())
 
+    pub fn is_snapshot(&self) -> bool {
+        self.snapshot
+    }
 }
 
-fn download_java_unpack_targz(targz_path: PathBuf, save_path: PathBuf) -> Result<(), LibError> {
-    let targz = File::open(targz_path.clone())?;
-    let tar = GzDecoder::new(targz);
-    let mut archive =Archive::new(tar);
-    for entry in archive.entries()? {
-        let mut entry = entry?;
+// Newest first. BungeeCord has no versions of its own, its successful Jenkins builds stand in for them.
+pub fn meta_fetch_proxy_versions(software: ProxySoftware) -> Result<Vec<ProxyVersion>, LibError> {
+    let mut versions = match software {
+        ProxySoftware::BungeeCord => download_bungeecord_fetch_builds()?
+            .iter()
+            .filter(|build| build.result.as_deref() == Some("SUCCESS") && download_bungeecord_artifact(build).is_some())
+            .map(|build| ProxyVersion::parse(&build.number.to_string()))
+            .collect::<Result<Vec<ProxyVersion>, LibError>>()?,
+        software => download_paper_fetch_project(download_paper_proxy_project_name(software))?
+            .versions
+            .iter()
+            .map(|id| ProxyVersion::parse(id))
+            .collect::<Result<Vec<ProxyVersion>, LibError>>()?,
+    };
+    versions.sort_by(|a, b| b.cmp(a));
+    Ok(versions)
+}
 
-        let path = entry.path()?;
-        let stripped: PathBuf = path.components().skip(1).collect();
+// `latest` is the newest version, a release only wins over a snapshot of the same numbers.
+// Velocity still lists old releases like 3.1.1 next to the current 3.4.0-SNAPSHOT.
+pub fn meta_resolve_proxy_version(software: ProxySoftware, ver: &str) -> Result<ProxyVersion, LibError> {
+    let versions = meta_fetch_proxy_versions(software)?;
+    let found = match ver.trim() {
+        "latest" => versions.first(),
+        id => versions.iter().find(|ver| ver.id() == id),
+    };
+    found.cloned().ok_or_else(|| LibError::VersionNotFound {
+        loader: software.to_string(),
+        version: ver.to_owned(),
+    })
+}
 
-        if stripped.as_os_str().is_empty() {
-            continue;
-        }
+// Releases sort above snapshots of the same numbers.
+impl PartialOrd for ProxyVersion {
+    fn partial_cmp(&self, other: &ProxyVersion) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
 
-        entry.unpack(save_path.join(stripped))?;
+impl Ord for ProxyVersion {
+    fn cmp(&self, other: &ProxyVersion) -> CmpOrdering {
+        minecraft_version_cmp_numbers(&self.numbers, &other.numbers)
+            .then_with(|| other.snapshot.cmp(&self.snapshot))
+            .then_with(|| self.id.cmp(&other.id))
     }
-    fs::remove_file(targz_path)?;
-    Ok(())
 }
 
-fn download_java_unpack_zip(zip_path: PathBuf, save_path: PathBuf) -> Result<(), LibError> {
-    let file = File::open(&zip_path)?;
-    let mut archive = ZipArchive::new(file)?;
+version_id_impls!(ProxyVersion);
 
-    for i in 0..archive.len() {
-        let mut entry = archive.by_index(i)?;
+//
+// Bedrock Versions
+//
 
-        // Skip directories
-        let path = match entry.enclosed_name() {
-            Some(path) => path,
-            None => continue, // security: skip paths with traversal
-        };
+// Bedrock versions have four components, e.g. 1.21.51.02. The id keeps the zero padding the download URLs use.
+#[derive(Clone, Debug)]
+pub struct BedrockVersion {
+    id: String,
+    numbers: Vec<u32>,
+    // Set when resolved from the links API, previews are published under a different path than releases.
+    download_url: Option<String>,
+}
 
-        // Strip the top-level directory
-        let stripped: PathBuf = path.components().skip(1).collect();
-        if stripped.as_os_str().is_empty() {
-            continue;
-        }
+impl BedrockVersion {
+    pub fn parse(id: &str) -> Result<BedrockVersion, LibError> {
+        let id = id.trim();
+        let numbers = minecraft_version_parse_numbers(id)
+            .ok_or_else(|| LibError::Ver(format!("invalid Bedrock version {id}")))?;
+        Ok(BedrockVersion {
+            id: id.to_owned(),
+            numbers,
+            download_url: None,
+        })
+    }
 
-        let out_path = save_path.join(&stripped);
+    pub fn id(&self) -> &str {
+        &self.id
+    }
 
-        if entry.is_dir() {
-            fs::create_dir_all(&out_path)?;
-        } else {
-            if let Some(parent) = out_path.parent() {
-                fs::create_dir_all(parent)?;
-            }
-            let mut outfile = File::create(&out_path)?;
-            std::io::copy(&mut entry, &mut outfile)?;
-        }
+    pub fn numbers(&self) -> &[u32] {
+        &self.numbers
     }
 
-    fs::remove_file(zip_path)?;
-    Ok(())
+    // Versions pinned by number are assumed to be releases.
+    pub fn download_url(&self) -> String {
+        match &self.download_url {
+            Some(url) => url.clone(),
+            None => format!("{BEDROCK_DOWNLOAD_URL}/bedrockdedicatedserver/bin-linux/bedrock-server-{}.zip", self.id),
+        }
+    }
 }
 
-//
-// Metadata
-//
-
-#[derive(Clone, Copy, Debug, ValueEnum, PartialEq)]
-pub enum Modloaders {
-    Vanilla,
-    Forge,
-    NeoForge,
-    Fabric,
-    Paper,
-    Folia,
+// Mojang only announces the current release and preview, older versions can still be pinned by their number.
+pub fn meta_resolve_bedrock_version(ver: &str) -> Result<BedrockVersion, LibError> {
+    let download_type = match ver.trim() {
+        BEDROCK_LATEST_ALIAS => "serverBedrockLinux",
+        BEDROCK_PREVIEW_ALIAS => "serverBedrockPreviewLinux",
+        id => return BedrockVersion::parse(id),
+    };
+    let text = cache_fetch_text(&format!("{BEDROCK_LINKS_URL}/api/v1.0/download/links"))?;
+    let response: BedrockLinksResponse = serde_json::from_str(&text)?;
+    response.result.links
+        .iter()
+        .find(|link| link.download_type == download_type)
+        .and_then(|link| {
+            let (_, file) = link.download_url.rsplit_once("bedrock-server-")?;
+            let id = file.strip_suffix(".zip")?;
+            Some(BedrockVersion::parse(id).map(|version| BedrockVersion {
+                download_url: Some(link.download_url.clone()),
+                ..version
+            }))
+        })
+        .unwrap_or_else(|| Err(LibError::VersionNotFound {
+            loader: "Bedrock".to_owned(),
+            version: ver.to_owned(),
+        }))
 }
- 
-pub fn meta_fetch_game_versions() -> Result<Vec<String>, LibError> {
-    let mut result: Vec<String> = Vec::new();
-    let mut response = ureq::get("https://piston-meta.mojang.com/mc/game/version_manifest_v2.json")
-    .call()?;
 
-    let body = response.body_mut();
-    let text = body.read_to_string()?;
+impl PartialOrd for BedrockVersion {
+    fn partial_cmp(&self, other: &BedrockVersion) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
 
-    let manifest: MojangVersionManifest = serde_json::from_str(&text)?;
-    let versions: Vec<MojangVersionEntry> = manifest.versions;
-    for ver in versions {
-        if ver.kind == "release" {
-            result.push(ver.id);
-        }
+impl Ord for BedrockVersion {
+    fn cmp(&self, other: &BedrockVersion) -> CmpOrdering {
+        minecraft_version_cmp_numbers(&self.numbers, &other.numbers).then_with(|| self.id.cmp(&other.id))
     }
-    Ok(result)
 }
 
+version_id_impls!(BedrockVersion);
+
 //
 // Forge Versions
 //
@@ -823,27 +3975,382 @@ where
     Ok(result)
 }
 
-fn meta_get_forge_version_for_corresponding_mc_version(ver: String) -> Result<String, LibError> {
-    let mut response = ureq::get("https://files.minecraftforge.net/net/minecraftforge/forge/maven-metadata.json")
-        .call()?;
+#[derive(Debug, Deserialize)]
+pub struct ForgePromotions {
+    pub promos: HashMap<String, String>,
+}
+
+// Returns the full Forge build id, e.g. 1.20.1-47.2.0.
+// Recommended falls back to the latest promotion when a Minecraft version has no recommended build yet.
+pub fn meta_resolve_forge_build(ver: &MinecraftVersion, selection: &BuildSelection) -> Result<String, LibError> {
+    let meta = meta_fetch_forge_metadata()?;
+
+    let mut builds: Vec<String> = match meta.minecraft_versions.get(ver.id()) {
+        Some(forge_version) => forge_version.builds.iter().map(|build| build.id.clone()).collect(),
+        None => Vec::new(),
+    };
+    builds.sort_by_key(|build| meta_forge_build_numbers(ver, build));
+
+    let no_build = || LibError::NoBuild {
+        loader: "Forge".to_owned(),
+        version: ver.to_string(),
+    };
+    if builds.is_empty() {
+        return Err(no_build());
+    }
+
+    let forge_version = match selection {
+        BuildSelection::Pinned(build) => build.clone(),
+        BuildSelection::Recommended | BuildSelection::Latest => {
+            let text = cache_fetch_text(&format!("{FORGE_FILES_URL}/net/minecraftforge/forge/promotions_slim.json"))?;
+            let promotions: ForgePromotions = serde_json::from_str(&text)?;
+            let recommended = promotions.promos.get(&format!("{ver}-recommended"));
+            let latest = promotions.promos.get(&format!("{ver}-latest"));
+            let promoted = match selection {
+                BuildSelection::Recommended => recommended.or(latest),
+                _ => latest,
+            };
+            match promoted {
+                Some(promoted) => promoted.clone(),
+                None => return builds.last().cloned().ok_or_else(no_build),
+            }
+        }
+    };
+
+    // Accept both 47.2.0 and 1.20.1-47.2.0, older builds also carry a branch suffix (1.7.10-10.13.4.1614-1.7.10)
+    let prefixed = format!("{ver}-{forge_version}");
+    builds
+        .into_iter()
+        .find(|build| *build == forge_version || *build == prefixed || build.starts_with(&format!("{prefixed}-")))
+        .ok_or_else(|| LibError::BuildNotFound {
+            loader: "Forge".to_owned(),
+            version: ver.to_string(),
+            build: forge_version,
+        })
+}
 
-    let body = response.body_mut();
-    let text = body.read_to_string()?;
+pub fn meta_fetch_forge_metadata() -> Result<ForgeMetadata, LibError> {
+    let text = cache_fetch_text(&format!("{FORGE_FILES_URL}/net/minecraftforge/forge/maven-metadata.json"))?;
     let meta: ForgeMetadata = serde_json::from_str(&text)?;
+    Ok(meta)
+}
+
+fn meta_forge_build_numbers(ver: &MinecraftVersion, build: &str) -> Vec<u32> {
+    let forge_version = build.strip_prefix(&format!("{ver}-")).unwrap_or(build);
+    let forge_version = forge_version.split('-').next().unwrap_or(forge_version);
+    forge_version.split('.').map(|part| part.parse::<u32>().unwrap_or(0)).collect()
+}
+
+//
+// NeoForge Versions
+//
 
-    let mut builds: Vec<String> = vec![];
+#[derive(Debug, Deserialize)]
+pub struct NeoForgeVersions {
+    #[serde(rename = "isSnapshot")]
+    pub is_snapshot: bool,
+    pub versions: Vec<String>,
+}
 
-    for (mc_version, forge_version) in &meta.minecraft_versions {
-        for build in &forge_version.builds {
-            if ver == *mc_version {
-                let build_tmp = build.id.replace(mc_version, "");
-                let build_flat = build_tmp.replace("-", "");
-                builds.push(build_flat);
+// NeoForge drops the leading "1." of the Minecraft version: 1.21.1 -> 21.1.x, 1.21 -> 21.0.x.
+// Year based Minecraft versions keep their numbers: 26.1 -> 26.1.0.x
+// 1.20.1 was published under the old net.neoforged:forge artifact and is not supported here.
+pub fn meta_neoforge_version_prefix(ver: &MinecraftVersion) -> Option<String> {
+    if !ver.is_release() {
+        return None;
+    }
+    match ver.numbers() {
+        [1, minor, rest @ ..] if *minor >= 20 => {
+            let patch = rest.first().copied().unwrap_or(0);
+            if *minor == 20 && patch < 2 {
+                return None;
             }
+            Some(format!("{minor}.{patch}."))
         }
+        [major, minor, rest @ ..] if *major > 1 => {
+            let patch = rest.first().copied().unwrap_or(0);
+            Some(format!("{major}.{minor}.{patch}."))
+        }
+        _ => None,
+    }
+}
+
+// Recommended picks the newest build without a -beta/-alpha suffix and falls back to the newest build.
+pub fn meta_resolve_neoforge_build(ver: &MinecraftVersion, selection: &BuildSelection) -> Result<String, LibError> {
+    let no_build = || LibError::NoBuild {
+        loader: "NeoForge".to_owned(),
+        version: ver.to_string(),
+    };
+    let prefix = meta_neoforge_version_prefix(ver).ok_or_else(no_build)?;
+
+    let meta = meta_fetch_neoforge_versions()?;
+
+    let mut builds: Vec<String> = meta.versions
+        .into_iter()
+        .filter(|build| build.starts_with(&prefix))
+        .collect();
+    builds.sort_by_key(|build| meta_neoforge_build_numbers(build));
+
+    match selection {
+        BuildSelection::Pinned(build) => builds
+            .into_iter()
+            .find(|candidate| candidate == build)
+            .ok_or_else(|| LibError::BuildNotFound {
+                loader: "NeoForge".to_owned(),
+                version: ver.to_string(),
+                build: build.clone(),
+            }),
+        BuildSelection::Recommended => builds
+            .iter()
+            .rev()
+            .find(|build| !build.contains('-'))
+            .or(builds.last())
+            .cloned()
+            .ok_or_else(no_build),
+        BuildSelection::Latest => builds.last().cloned().ok_or_else(no_build),
     }
+}
+
+pub fn meta_fetch_neoforge_versions() -> Result<NeoForgeVersions, LibError> {
+    let text = cache_fetch_text(&format!("{NEOFORGE_MAVEN_URL}/api/maven/versions/releases/net/neoforged/neoforge"))?;
+    let meta: NeoForgeVersions = serde_json::from_str(&text)?;
+    Ok(meta)
+}
+
+fn meta_neoforge_build_numbers(build: &str) -> Vec<u32> {
+    let numbers = build.split('-').next().unwrap_or(build);
+    numbers.split('.').map(|part| part.parse::<u32>().unwrap_or(0)).collect()
+}
+
+//
+// Fabric Versions
+//
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FabricLoaderEntry {
+    pub loader: FabricLoader,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FabricLoader {
+    pub version: String,
+    pub stable: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FabricGameVersion {
+    pub version: String,
+    pub stable: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FabricInstallerJar {
+    pub url: String,
+    pub version: String,
+    pub stable: bool,
+}
+
+// Fabric meta lists loaders compatible with the game version, newest first.
+pub fn meta_resolve_fabric_loader(ver: &MinecraftVersion, selection: &BuildSelection) -> Result<FabricLoader, LibError> {
+    let text = cache_fetch_text(&format!("{FABRIC_META_URL}/v2/versions/loader/{ver}"))?;
+    let entries: Vec<FabricLoaderEntry> = serde_json::from_str(&text)?;
+    let loaders: Vec<FabricLoader> = entries.into_iter().map(|entry| entry.loader).collect();
+    meta_select_fabric_build(loaders, selection, |loader| (&loader.version, loader.stable), "Fabric loader", ver.id())
+}
+
+pub fn meta_resolve_fabric_installer(selection: &BuildSelection) -> Result<FabricInstallerJar, LibError> {
+    let text = cache_fetch_text(&format!("{FABRIC_META_URL}/v2/versions/installer"))?;
+    let installers: Vec<FabricInstallerJar> = serde_json::from_str(&text)?;
+    meta_select_fabric_build(installers, selection, |installer| (&installer.version, installer.stable), "Fabric installer", "any")
+}
+
+// Game versions Fabric has intermediary mappings for, newest first.
+pub fn meta_fetch_fabric_game_versions() -> Result<Vec<FabricGameVersion>, LibError> {
+    let text = cache_fetch_text(&format!("{FABRIC_META_URL}/v2/versions/game"))?;
+    let versions: Vec<FabricGameVersion> = serde_json::from_str(&text)?;
+    Ok(versions)
+}
+
+fn meta_select_fabric_build<T>(
+    builds: Vec<T>,
+    selection: &BuildSelection,
+    describe: impl Fn(&T) -> (&String, bool),
+    loader: &str,
+    version: &str,
+) -> Result<T, LibError> {
+    let no_build = || LibError::NoBuild {
+        loader: loader.to_owned(),
+        version: version.to_owned(),
+    };
+    let index = match selection {
+        BuildSelection::Pinned(build) => match builds.iter().position(|b| describe(b).0 == build) {
+            Some(index) => index,
+            None => {
+                return Err(LibError::BuildNotFound {
+                    loader: loader.to_owned(),
+                    version: version.to_owned(),
+                    build: build.clone(),
+                })
+            }
+        },
+        BuildSelection::Recommended => builds.iter().position(|b| describe(b).1).unwrap_or(0),
+        BuildSelection::Latest => 0,
+    };
+    builds.into_iter().nth(index).ok_or_else(no_build)
+}
+
+//
+// Quilt Versions
+//
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct QuiltLoaderEntry {
+    pub loader: QuiltLoader,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct QuiltLoader {
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct QuiltInstallerJar {
+    pub url: String,
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct QuiltGameVersion {
+    pub version: String,
+    pub stable: bool,
+}
+
+// Quilt meta has no stable flag, pre-releases carry a suffix (0.27.0-beta.1) and are skipped by recommended.
+pub fn meta_resolve_quilt_loader(ver: &MinecraftVersion, selection: &BuildSelection) -> Result<QuiltLoader, LibError> {
+    let text = cache_fetch_text(&format!("{QUILT_META_URL}/v3/versions/loader/{ver}"))?;
+    let entries: Vec<QuiltLoaderEntry> = serde_json::from_str(&text)?;
+    let loaders: Vec<QuiltLoader> = entries.into_iter().map(|entry| entry.loader).collect();
+    meta_select_fabric_build(loaders, selection, |loader| (&loader.version, !loader.version.contains('-')), "Quilt loader", ver.id())
+}
+
+pub fn meta_resolve_quilt_installer(selection: &BuildSelection) -> Result<QuiltInstallerJar, LibError> {
+    let text = cache_fetch_text(&format!("{QUILT_META_URL}/v3/versions/installer"))?;
+    let installers: Vec<QuiltInstallerJar> = serde_json::from_str(&text)?;
+    meta_select_fabric_build(installers, selection, |installer| (&installer.version, !installer.version.contains('-')), "Quilt installer", "any")
+}
+
+pub fn meta_fetch_quilt_game_versions() -> Result<Vec<QuiltGameVersion>, LibError> {
+    let text = cache_fetch_text(&format!("{QUILT_META_URL}/v3/versions/game"))?;
+    let versions: Vec<QuiltGameVersion> = serde_json::from_str(&text)?;
+    Ok(versions)
+}
+
+//
+// Sponge Versions
+//
+
+#[derive(Debug, Deserialize)]
+pub struct SpongeArtifact {
+    pub tags: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpongeVersions {
+    pub artifacts: HashMap<String, SpongeVersionEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpongeVersionEntry {
+    #[serde(rename = "tagValues")]
+    pub tag_values: HashMap<String, String>,
+    #[serde(default)]
+    pub recommended: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpongeVersion {
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    pub assets: Vec<SpongeAsset>,
+    #[serde(default)]
+    pub recommended: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpongeAsset {
+    pub classifier: String,
+    #[serde(rename = "downloadUrl")]
+    pub download_url: String,
+    pub sha1: Option<String>,
+    pub extension: String,
+}
+
+fn meta_sponge_artifact_name(forge: bool) -> &'static str {
+    if forge { "spongeforge" } else { "spongevanilla" }
+}
+
+pub fn meta_fetch_sponge_artifact(forge: bool) -> Result<SpongeArtifact, LibError> {
+    let artifact = meta_sponge_artifact_name(forge);
+    let text = cache_fetch_text(&format!("{SPONGE_API_URL}/v2/groups/org.spongepowered/artifacts/{artifact}"))?;
+    let artifact: SpongeArtifact = serde_json::from_str(&text)?;
+    Ok(artifact)
+}
+
+// Returns the Sponge version id with its build. The API lists versions newest first, so one entry is enough.
+// Recommended falls back to the newest build when no build of the Minecraft/API version is recommended yet.
+pub fn meta_resolve_sponge_build(ver: &MinecraftVersion, forge: bool, options: &SpongeOptions) -> Result<(String, SpongeVersion), LibError> {
+    let artifact = meta_sponge_artifact_name(forge);
+    let loader = if forge { "SpongeForge" } else { "SpongeVanilla" };
+
+    let sponge_version = match &options.build {
+        BuildSelection::Pinned(pinned) => pinned.clone(),
+        selection => {
+            let mut tags = format!("minecraft:{ver}");
+            if let Some(api) = &options.api {
+                tags.push_str(&format!(",api:{api}"));
+            }
+            // recommended=false would only list builds that are not recommended, so it is left out instead
+            let query = |recommended: bool| -> Result<Option<String>, LibError> {
+                let filter = if recommended { "&recommended=true" } else { "" };
+                let text = cache_fetch_text(&format!(
+                    "{SPONGE_API_URL}/v2/groups/org.spongepowered/artifacts/{artifact}/versions?tags={tags}{filter}&limit=1"
+                ))?;
+                let versions: SpongeVersions = serde_json::from_str(&text)?;
+                Ok(versions.artifacts.into_keys().next())
+            };
+            let found = match selection {
+                BuildSelection::Recommended => match query(true)? {
+                    Some(version) => Some(version),
+                    None => query(false)?,
+                },
+                _ => query(false)?,
+            };
+            found.ok_or_else(|| LibError::NoBuild {
+                loader: loader.to_owned(),
+                version: ver.to_string(),
+            })?
+        }
+    };
 
-    Ok(builds[builds.len()-1].clone())
+    let not_found = || LibError::BuildNotFound {
+        loader: loader.to_owned(),
+        version: ver.to_string(),
+        build: sponge_version.clone(),
+    };
+    let text = cache_fetch_text(&format!("{SPONGE_API_URL}/v2/groups/org.spongepowered/artifacts/{artifact}/versions/{sponge_version}"))
+        .map_err(|e| match e {
+            LibError::HttpStatus { status: 404, .. } => not_found(),
+            e => e,
+        })?;
+    let build: SpongeVersion = serde_json::from_str(&text)?;
+    if build.tags.get("minecraft").map(String::as_str) != Some(ver.id()) {
+        return Err(not_found());
+    }
+    if let Some(api) = &options.api
+        && build.tags.get("api") != Some(api) {
+        return Err(not_found());
+    }
+    Ok((sponge_version, build))
 }
 
 //
@@ -855,6 +4362,12 @@ pub struct Config {
     pub title: String,
     pub version: String,
     pub directories: Directories,
+    #[serde(default)]
+    pub sources: Sources,
+    #[serde(default)]
+    pub cache: CacheSettings,
+    #[serde(default)]
+    pub java: JavaSettings,
 }
 
 #[derive(Serialize)]
@@ -890,15 +4403,26 @@ fn config_fetch_directories() -> Directories {
         home_dir = user_dirs.home_dir().to_string_lossy().to_string();
     }
     
-    let dirs = Directories {
-        config_dir: config_dir,
-        cache_dir: cache_dir,
+    Directories {
+        config_dir,
+        cache_dir,
         data_dir: data_dir.clone(),
-        home_dir: home_dir,
-        server_dir: server_dir,
+        home_dir,
+        server_dir,
         java_dir: data_dir + "/java"
-    };
-    return dirs;
+    }
+}
+
+fn config_default_config() -> Config {
+    let dirs = config_fetch_directories();
+    Config {
+        title: APP_NAME.to_owned(),
+        version: APP_VERSION.to_owned(),
+        directories: dirs,
+        sources: Sources::default(),
+        cache: CacheSettings::default(),
+        java: JavaSettings::default(),
+    }
 }
 
 pub fn config_create_config() -> Result<(), LibError> {
@@ -910,21 +4434,7 @@ pub fn config_create_config() -> Result<(), LibError> {
         if !config_dir.exists() {
             std::fs::create_dir_all(&config_dir)?;
         }
-
-        let config = Config {
-            title: APP_NAME.to_owned(),
-            version: APP_VERSION.to_owned(),
-            directories: Directories {
-                config_dir: dirs.config_dir,
-                data_dir: dirs.data_dir,
-                cache_dir: dirs.cache_dir,
-                home_dir: dirs.home_dir,
-                server_dir: dirs.server_dir,
-                java_dir: dirs.java_dir,
-            },
-        };
-
-        config_write_config(&config)?;
+        config_write_config(&config_default_config())?;
     }
 
     Ok(())
@@ -934,41 +4444,251 @@ pub fn config_read_config() -> Result<Config, LibError> {
     let dirs = config_fetch_directories();
     let path = PathBuf::from(dirs.config_dir).join("config.toml");
 
-    let content = std::fs::read_to_string(path)?;
+    let content = std::fs::read_to_string(&path)?;
     let config: Config = toml::from_str(&content)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        .map_err(|e| config_parse_error(&path, &content, e))?;
 
     Ok(config)
 }
 
+fn config_parse_error(path: &Path, content: &str, error: toml::de::Error) -> LibError {
+    let line = match error.span() {
+        Some(span) => content.get(..span.start).unwrap_or(content).matches('\n').count() + 1,
+        None => 0,
+    };
+    LibError::ConfigParse {
+        file: path.to_string_lossy().to_string(),
+        line,
+        message: error.message().to_owned(),
+    }
+}
+
 pub fn config_write_config(config: &Config) -> Result<(), LibError> {
     let dirs = config_fetch_directories();
     let path = PathBuf::from(dirs.config_dir).join("config.toml");
 
     let toml_string = toml::to_string_pretty(config)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        .map_err(std::io::Error::other)?;
 
     std::fs::write(path, toml_string)?;
     Ok(())
 }
 
+// Library functions may run before `config_create_config`, so fall back to the defaults.
+pub fn config_read_config_or_default() -> Result<Config, LibError> {
+    let dirs = config_fetch_directories();
+    if !PathBuf::from(dirs.config_dir).join("config.toml").exists() {
+        return Ok(config_default_config());
+    }
+    config_read_config()
+}
+
+pub fn config_read_sources() -> Result<Sources, LibError> {
+    Ok(config_read_config_or_default()?.sources)
+}
+
 pub fn config_collect_java_bin_path(java_ver: JavaVersion) -> Result<String, LibError> {
     let config = config_read_config()?;
     let java_base_dir = config.directories.java_dir;
     let ver_path = PathBuf::from(&java_base_dir).join(java_ver.to_string()).join("bin").join("java");
-    match Command::new(ver_path.clone())
+    if config_java_runs(&ver_path) {
+        return Ok(ver_path.to_string_lossy().to_string());
+    }
+    // Registered host runtimes have to match the major version exactly, old Forge breaks on anything newer than 8
+    let major = java_major(java_ver);
+    for installation in config.java.installations.iter().filter(|installation| installation.major == major) {
+        if config_java_runs(Path::new(&installation.java_bin)) {
+            return Ok(installation.java_bin.clone());
+        }
+    }
+    Err(LibError::JavaNotFound {
+        java: java_ver.to_string(),
+        path: ver_path.to_string_lossy().to_string(),
+    })
+}
+
+fn config_java_runs(java_bin: &Path) -> bool {
+    Command::new(java_bin)
     .arg("-version")
     .stdin(Stdio::null())
     .stdout(Stdio::null())
     .stderr(Stdio::null())
-    .status() 
-    {
-        Ok(_) => return Ok(ver_path.to_string_lossy().to_string()),
-        Err(e) => return Err(LibError::Io(e))
-    }
+    .status()
+    .is_ok()
 }
 
 
 //
 // Server Structs
-//
\ No newline at end of file
+//
+
+// Written into every instance directory by the installers, so later operations know what is installed there.
+pub const INSTANCE_FILE_NAME: &str = "instance.toml";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Instance {
+    pub loader: Modloaders,
+    pub mc_version: MinecraftVersion,
+    pub build: Option<String>,
+    // Which content folders the server reads, instances written before this was recorded fall back to the loader's.
+    #[serde(default)]
+    pub content: Vec<ContentKind>,
+}
+
+impl Instance {
+    pub fn new(loader: Modloaders, mc_version: MinecraftVersion, build: Option<String>) -> Instance {
+        Instance {
+            loader,
+            mc_version,
+            build,
+            content: loader.content_kinds().to_vec(),
+        }
+    }
+
+    pub fn content_kinds(&self) -> &[ContentKind] {
+        if self.content.is_empty() {
+            self.loader.content_kinds()
+        } else {
+            &self.content
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProxyInstance {
+    pub proxy: ProxySoftware,
+    pub version: ProxyVersion,
+    pub build: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BedrockInstance {
+    pub bedrock_version: BedrockVersion,
+}
+
+// All kinds share the instance file, they are told apart by their `proxy`, `bedrock_version` or `loader` key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum InstanceKind {
+    Proxy(ProxyInstance),
+    Bedrock(BedrockInstance),
+    Server(Instance),
+}
+
+impl InstanceKind {
+    // The installer registry key for whatever the instance runs.
+    pub fn server_kind(&self) -> ServerKind {
+        match self {
+            InstanceKind::Proxy(instance) => ServerKind::Proxy(instance.proxy),
+            InstanceKind::Bedrock(_) => ServerKind::Bedrock,
+            InstanceKind::Server(instance) => ServerKind::Java(instance.loader),
+        }
+    }
+}
+
+pub fn instance_read(path: &Path) -> Result<Instance, LibError> {
+    let file = path.join(INSTANCE_FILE_NAME);
+    let content = fs::read_to_string(&file)?;
+    let instance: Instance = toml::from_str(&content)
+        .map_err(|e| config_parse_error(&file, &content, e))?;
+    Ok(instance)
+}
+
+// Any of the InstanceKind payloads, each writes its own keys.
+pub fn instance_write<T: Serialize>(path: &Path, instance: &T) -> Result<(), LibError> {
+    let toml_string = toml::to_string_pretty(instance)
+        .map_err(std::io::Error::other)?;
+    fs::write(path.join(INSTANCE_FILE_NAME), toml_string)?;
+    Ok(())
+}
+
+pub fn instance_read_kind(path: &Path) -> Result<InstanceKind, LibError> {
+    let file = path.join(INSTANCE_FILE_NAME);
+    let content = fs::read_to_string(&file)?;
+    let instance: InstanceKind = toml::from_str(&content)
+        .map_err(|e| config_parse_error(&file, &content, e))?;
+    Ok(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str) -> MinecraftVersion {
+        MinecraftVersion::parse(id).unwrap()
+    }
+
+    #[test]
+    fn minecraft_version_parses_kinds() {
+        assert_eq!(version("1.20.5").kind(), &MinecraftVersionKind::Release);
+        assert_eq!(version("1.20.5-pre2").kind(), &MinecraftVersionKind::PreRelease(2));
+        assert_eq!(version("1.20.5-rc1").kind(), &MinecraftVersionKind::ReleaseCandidate(1));
+        assert_eq!(version("1.20.5-rc1").numbers(), &[1, 20, 5]);
+        assert_eq!(
+            version("24w14a").kind(),
+            &MinecraftVersionKind::Snapshot { year: 24, week: 14, suffix: "a".to_owned() }
+        );
+        assert_eq!(version("24w\u{20ac}1").kind(), &MinecraftVersionKind::Other);
+        assert!(MinecraftVersion::parse(" ").is_err());
+    }
+
+    #[test]
+    fn minecraft_version_orders_release_after_pre_and_rc() {
+        assert!(version("1.20.5-pre1") < version("1.20.5-pre2"));
+        assert!(version("1.20.5-pre2") < version("1.20.5-rc1"));
+        assert!(version("1.20.5-rc1") < version("1.20.5"));
+        assert!(version("1.20.5") < version("1.20.6-pre1"));
+        assert!(version("1.9") < version("1.10"));
+    }
+
+    #[test]
+    fn minecraft_version_orders_weekly_snapshots_among_themselves() {
+        assert!(version("24w14a") < version("24w14b"));
+        assert!(version("23w51a") < version("24w03a"));
+        assert_eq!(version("24w14a").partial_cmp(&version("1.20.4")), None);
+        assert_eq!(version("1.20.5").partial_cmp(&version("24w14a")), None);
+    }
+
+    #[test]
+    fn minecraft_version_orders_by_release_time() {
+        let mut versions = vec![
+            version("1.20.5").with_release_time("2024-04-23T12:00:00+00:00"),
+            version("24w14a").with_release_time("2024-04-03T12:00:00+00:00"),
+            version("1.20.4").with_release_time("2023-12-07T12:00:00+00:00"),
+            version("24w13a").with_release_time("2024-03-27T12:00:00+00:00"),
+            version("1.20.5-rc1").with_release_time("2024-04-18T12:00:00+00:00"),
+        ];
+        versions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let ids: Vec<&str> = versions.iter().map(|ver| ver.id()).collect();
+        assert_eq!(ids, ["1.20.4", "24w13a", "24w14a", "1.20.5-rc1", "1.20.5"]);
+        assert!(version("24w14a").with_release_time("2024-04-03T12:00:00+00:00") > version("1.20.4").with_release_time("2023-12-07T12:00:00+00:00"));
+        // Releases without a release time still order among themselves
+        assert!(version("1.20.4") < version("1.20.5").with_release_time("2024-04-23T12:00:00+00:00"));
+    }
+
+    #[test]
+    fn server_kind_parses_loaders_and_proxies() {
+        assert_eq!("paper".parse::<ServerKind>().unwrap(), ServerKind::Java(Modloaders::Paper));
+        assert_eq!("neo-forge".parse::<ServerKind>().unwrap(), ServerKind::Java(Modloaders::NeoForge));
+        assert_eq!("velocity".parse::<ServerKind>().unwrap(), ServerKind::Proxy(ProxySoftware::Velocity));
+        assert_eq!("bungee-cord".parse::<ServerKind>().unwrap(), ServerKind::Proxy(ProxySoftware::BungeeCord));
+        assert_eq!("bedrock".parse::<ServerKind>().unwrap(), ServerKind::Bedrock);
+        assert!("spigot".parse::<ServerKind>().is_err());
+    }
+
+    #[test]
+    fn proxy_version_orders_newer_snapshots_above_old_releases() {
+        let proxy = |id: &str| ProxyVersion::parse(id).unwrap();
+        assert!(proxy("3.1.1") < proxy("3.4.0-SNAPSHOT"));
+        assert!(proxy("3.4.0-SNAPSHOT") < proxy("3.4.0"));
+        assert!(proxy("1.1.9") < proxy("3.1.0"));
+    }
+
+    #[test]
+    fn link_header_yields_next_page() {
+        let link = "<https://api.github.com/repositories/1/releases?per_page=100&page=2>; rel=\"next\", \
+                    <https://api.github.com/repositories/1/releases?per_page=100&page=4>; rel=\"last\"";
+        assert_eq!(cache_link_next(link).as_deref(), Some("https://api.github.com/repositories/1/releases?per_page=100&page=2"));
+        assert_eq!(cache_link_next("<https://api.github.com/repositories/1/releases?page=1>; rel=\"first\""), None);
+    }
+}